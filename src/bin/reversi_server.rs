@@ -0,0 +1,33 @@
+use reversi_iced::server::{self, Options, Server};
+use std::{path::PathBuf, process};
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{}", server::USAGE);
+            process::exit(2)
+        }
+    };
+    if options.help {
+        println!("{}", server::USAGE);
+        return;
+    }
+    let games = options
+        .games
+        .or_else(server::games_path)
+        .unwrap_or_else(|| PathBuf::from("games"));
+    let server = match Server::bind(options.port, games.clone()) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("Couldn't listen on port {}: {error}", options.port);
+            process::exit(1)
+        }
+    };
+    eprintln!(
+        "Listening on port {}, keeping finished games in {}",
+        options.port,
+        games.display()
+    );
+    server.run()
+}
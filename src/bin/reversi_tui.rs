@@ -0,0 +1,24 @@
+use reversi_iced::{cli, tui};
+use std::process;
+
+fn main() {
+    let options = match cli::Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{}", cli::USAGE);
+            process::exit(2)
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if options.lan.is_some() {
+        eprintln!("LAN games are played in the window version");
+        process::exit(2)
+    }
+    if let Err(error) = tui::run(options) {
+        eprintln!("Couldn't run in this terminal: {error}");
+        process::exit(1)
+    }
+}
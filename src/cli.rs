@@ -0,0 +1,136 @@
+use crate::{
+    net::{self, Role},
+    save::{color_from_name, difficulty_from_name, mode_from_name, SavedGame},
+    Board, Difficulty, DisplaySize, Mode, StoneColor,
+};
+use std::fs;
+
+pub const USAGE: &str = "\
+Usage: reversi_iced [OPTIONS]
+
+Options:
+  --mode <computer|two-players|analysis>
+  --color <black|white>           The color you play against the computer
+  --difficulty <easy|medium|hard>
+  --display-size <small|medium|large|fit>
+  --game <FILE>                   Continue a game saved in FILE
+  --position <POSITION>           Start from a position: 64 squares of X, O or -,
+                                  a space, and X or O for the side to move
+  --host <PORT>                   Host a LAN game; the other player joins with --join
+  --join <ADDRESS>                Join a LAN game, e.g. 192.168.1.20 or 192.168.1.20:7878,
+                                  or a room on a server as ADDRESS/ROOM
+  --watch <ADDRESS>               Watch a LAN game or a server room without playing
+  --window-size <WIDTHxHEIGHT>    e.g. 1280x800
+  --fullscreen
+  -h, --help                      Print this and exit
+
+A game file or position, a LAN game, or any of the first three options, skips the menu.
+The options win over the saved settings.";
+
+/// What the game was started with. Anything left out comes from the settings.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Options {
+    pub mode: Option<Mode>,
+    pub color: Option<StoneColor>,
+    pub difficulty: Option<Difficulty>,
+    pub display_size: Option<DisplaySize>,
+    pub game: Option<SavedGame>,
+    pub position: Option<Board>,
+    pub lan: Option<Role>,
+    pub window_size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    pub help: bool,
+}
+
+impl Options {
+    /// Reads the arguments after the program name. Values go either after an `=` or
+    /// in the next argument. Game files are read straight away, so a bad one is
+    /// reported before any window opens.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+            match flag.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--fullscreen" => options.fullscreen = true,
+                "--mode" | "--color" | "--difficulty" | "--display-size" | "--game"
+                | "--position" | "--host" | "--join" | "--watch" | "--window-size" => {
+                    let value = inline
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("{flag} needs a value"))?;
+                    let invalid = || format!("invalid value for {flag}: {value}");
+                    match flag.as_str() {
+                        "--mode" => {
+                            options.mode = Some(mode_from_name(&value).ok_or_else(invalid)?)
+                        }
+                        "--color" => {
+                            options.color = Some(color_from_name(&value).ok_or_else(invalid)?)
+                        }
+                        "--difficulty" => {
+                            options.difficulty =
+                                Some(difficulty_from_name(&value).ok_or_else(invalid)?)
+                        }
+                        "--display-size" => {
+                            options.display_size =
+                                Some(DisplaySize::from_name(&value).ok_or_else(invalid)?)
+                        }
+                        "--game" => {
+                            let contents = fs::read_to_string(&value)
+                                .map_err(|error| format!("can't read {value}: {error}"))?;
+                            options.game = Some(
+                                SavedGame::from_file_string(&contents)
+                                    .ok_or_else(|| format!("{value} is not a saved game"))?,
+                            )
+                        }
+                        "--position" => {
+                            options.position =
+                                Some(Board::from_position_string(&value).ok_or_else(invalid)?)
+                        }
+                        "--host" => {
+                            options.lan = Some(Role::Host(value.parse().map_err(|_| invalid())?))
+                        }
+                        "--join" => options.lan = Some(Role::Join(net::with_default_port(&value))),
+                        "--watch" => {
+                            options.lan = Some(Role::Watch(net::with_default_port(&value)))
+                        }
+                        _ => options.window_size = Some(window_size(&value).ok_or_else(invalid)?),
+                    }
+                }
+                _ => return Err(format!("unknown argument: {flag}")),
+            }
+        }
+        if options.game.is_some() && options.position.is_some() {
+            return Err(String::from("--game and --position can't be used together"));
+        }
+        if let Some(Role::Join(_) | Role::Watch(_)) = &options.lan {
+            if options.game.is_some() || options.position.is_some() {
+                return Err(String::from(
+                    "--join and --watch follow the host's game, so they can't be used with \
+                     --game or --position",
+                ));
+            }
+        }
+        Ok(options)
+    }
+
+    /// Whether to go straight into a game instead of showing the menu.
+    pub fn starts_game(&self) -> bool {
+        self.mode.is_some()
+            || self.color.is_some()
+            || self.difficulty.is_some()
+            || self.game.is_some()
+            || self.position.is_some()
+            || self.lan.is_some()
+    }
+}
+
+/// `WIDTHxHEIGHT`, both positive.
+fn window_size(value: &str) -> Option<(u32, u32)> {
+    let (width, height) = value.split_once(['x', 'X'])?;
+    let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
+}
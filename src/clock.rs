@@ -0,0 +1,200 @@
+use crate::StoneColor;
+use std::time::{Duration, Instant};
+
+/// How much thinking time each side gets.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeControl {
+    #[default]
+    Unlimited,
+    /// A fixed amount for the whole game.
+    SuddenDeath(Duration),
+    /// Main time, plus a bonus added after every move.
+    Increment(Duration, Duration),
+    /// Main time, then a period that every move has to fit in once it runs out.
+    ByoYomi(Duration, Duration),
+}
+/// Choices the settings page cycles through.
+pub(crate) const MINUTES: [u64; 6] = [1, 3, 5, 10, 15, 30];
+pub(crate) const SECONDS: [u64; 4] = [2, 5, 10, 30];
+
+impl TimeControl {
+    /// Main time, zero for an untimed game.
+    pub fn main(&self) -> Duration {
+        match self {
+            Self::Unlimited => Duration::ZERO,
+            Self::SuddenDeath(main) | Self::Increment(main, _) | Self::ByoYomi(main, _) => *main,
+        }
+    }
+
+    /// The increment or byo-yomi period, zero if there is none.
+    pub fn extra(&self) -> Duration {
+        match self {
+            Self::Increment(_, extra) | Self::ByoYomi(_, extra) => *extra,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// The next kind of time control, keeping the times already picked.
+    pub fn next(&self) -> Self {
+        let main = match self.main() {
+            Duration::ZERO => Duration::from_secs(5 * 60),
+            main => main,
+        };
+        let extra = match self.extra() {
+            Duration::ZERO => Duration::from_secs(10),
+            extra => extra,
+        };
+        match self {
+            Self::Unlimited => Self::SuddenDeath(main),
+            Self::SuddenDeath(_) => Self::Increment(main, extra),
+            Self::Increment(..) => Self::ByoYomi(main, extra),
+            Self::ByoYomi(..) => Self::Unlimited,
+        }
+    }
+
+    /// The same kind of control with the next main time on the list.
+    pub fn next_main(&self) -> Self {
+        let main = Duration::from_secs(60 * next_on(&MINUTES, self.main().as_secs() / 60));
+        match *self {
+            Self::Unlimited => Self::Unlimited,
+            Self::SuddenDeath(_) => Self::SuddenDeath(main),
+            Self::Increment(_, extra) => Self::Increment(main, extra),
+            Self::ByoYomi(_, extra) => Self::ByoYomi(main, extra),
+        }
+    }
+
+    /// The same kind of control with the next increment or period on the list.
+    pub fn next_extra(&self) -> Self {
+        let extra = Duration::from_secs(next_on(&SECONDS, self.extra().as_secs()));
+        match *self {
+            Self::Increment(main, _) => Self::Increment(main, extra),
+            Self::ByoYomi(main, _) => Self::ByoYomi(main, extra),
+            other => other,
+        }
+    }
+}
+
+/// The first choice after `current`, wrapping around to the smallest.
+fn next_on(choices: &[u64], current: u64) -> u64 {
+    choices
+        .iter()
+        .copied()
+        .find(|&choice| choice > current)
+        .unwrap_or(choices[0])
+}
+
+/// Both sides' clocks. Only the side to move has its clock running, since `since`.
+#[derive(Clone, Debug)]
+pub struct Clocks {
+    control: TimeControl,
+    black: Duration,
+    white: Duration,
+    running: StoneColor,
+    since: Instant,
+}
+impl Clocks {
+    /// Starts `first`'s clock at `now`, or `None` for an untimed game.
+    pub fn new(control: TimeControl, first: StoneColor, now: Instant) -> Option<Self> {
+        let main = match control {
+            TimeControl::Unlimited => return None,
+            TimeControl::SuddenDeath(main)
+            | TimeControl::Increment(main, _)
+            | TimeControl::ByoYomi(main, _) => main,
+        };
+        Some(Clocks {
+            control,
+            black: main,
+            white: main,
+            running: first,
+            since: now,
+        })
+    }
+
+    fn main(&self, color: StoneColor) -> Duration {
+        match color {
+            StoneColor::Black => self.black,
+            StoneColor::White => self.white,
+        }
+    }
+
+    fn main_mut(&mut self, color: StoneColor) -> &mut Duration {
+        match color {
+            StoneColor::Black => &mut self.black,
+            StoneColor::White => &mut self.white,
+        }
+    }
+
+    /// How long the side to move has been thinking at `now`.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.since)
+    }
+
+    /// Takes a move that took `elapsed` off `color`'s clock and adds any increment.
+    /// In byo-yomi the period starts over for the next move.
+    pub fn spend(&mut self, color: StoneColor, elapsed: Duration) {
+        let control = self.control;
+        let main = self.main_mut(color);
+        *main = main.saturating_sub(elapsed);
+        if let TimeControl::Increment(_, increment) = control {
+            *main += increment
+        }
+    }
+
+    /// Hands the move to `color`, whose clock runs from `now`.
+    pub fn start(&mut self, color: StoneColor, now: Instant) {
+        self.running = color;
+        self.since = now;
+    }
+
+    /// Main time `color` has left at `now`.
+    pub fn remaining(&self, color: StoneColor, now: Instant) -> Duration {
+        match color == self.running {
+            true => self.main(color).saturating_sub(self.elapsed(now)),
+            false => self.main(color),
+        }
+    }
+
+    /// How far into byo-yomi `color` is on the current move.
+    fn overtime(&self, color: StoneColor, now: Instant) -> Duration {
+        match color == self.running {
+            true => self.elapsed(now).saturating_sub(self.main(color)),
+            false => Duration::ZERO,
+        }
+    }
+
+    /// The side whose time has run out at `now`, if any.
+    pub fn flagged(&self, now: Instant) -> Option<StoneColor> {
+        let color = self.running;
+        let out = match self.control {
+            TimeControl::ByoYomi(_, period) => self.overtime(color, now) >= period,
+            _ => self.remaining(color, now).is_zero(),
+        };
+        out.then_some(color)
+    }
+
+    /// How long the computer playing `color` may think about its next move: a slice of
+    /// its main time, plus most of the increment or byo-yomi period.
+    pub fn budget(&self, color: StoneColor, now: Instant) -> Duration {
+        let share = self.remaining(color, now) / 30;
+        match self.control {
+            TimeControl::Increment(_, extra) | TimeControl::ByoYomi(_, extra) => {
+                share + extra * 3 / 4
+            }
+            _ => share,
+        }
+    }
+
+    /// `m:ss` of main time left, or `BY m:ss` of the byo-yomi period once that is spent.
+    pub fn display(&self, color: StoneColor, now: Instant) -> String {
+        let main = self.remaining(color, now);
+        let (prefix, shown) = match self.control {
+            TimeControl::ByoYomi(_, period) if main.is_zero() => {
+                ("BY ", period.saturating_sub(self.overtime(color, now)))
+            }
+            _ => ("", main),
+        };
+        let seconds = shown.as_secs();
+        format!("{prefix}{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,1577 @@
+use crate::{
+    cli,
+    clock::Clocks,
+    index_to_pair, net, pair_to_index, review_game_from, save, settings, square_name, status_line,
+    themes, AnimationSpeed, Annotation, Board, Catalog, Difficulty, DisplaySize, Evaluation,
+    GameOutcome, GameOverItem, GameTheme, MenuItem, Message, Mode, Paths,
+    PlayerOrComputer::{self, *},
+    Ply, Search, StoneColor, TimeControl, HEIGHT, WIDTH,
+};
+use animation::Animation;
+use circles::circle;
+use graph::{EvaluationBar, EvaluationGraph};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    executor,
+    futures::{self, SinkExt, StreamExt},
+    keyboard::{self, key::Named, Key},
+    subscription, time,
+    widget::{
+        button, canvas, column, container, row, scrollable, text, text_input, Button, Column,
+        Container,
+    },
+    window, {theme, Length, Theme}, {Alignment, Application, Command, Element, Subscription},
+};
+use playboard::Playboard;
+use std::{
+    cmp::Ordering,
+    future, thread,
+    time::{Duration, Instant},
+};
+pub(crate) mod animation;
+mod circles;
+mod graph;
+pub(crate) mod playboard;
+
+pub struct Game {
+    pub(crate) game_board: Board,
+    /// The position `history` starts from.
+    opening: Board,
+    pub(crate) history: Vec<Ply>,
+    /// How many plies into `history` the board is being looked at, and that position,
+    /// while browsing the move list.
+    viewing: Option<(usize, Board)>,
+    /// Who had to pass during the last move, to tell the other player they go again.
+    pass_notice: Option<StoneColor>,
+    /// The engine's suggestions and the position they were asked for.
+    hint: Option<(String, Vec<Evaluation>)>,
+    /// The latest analysis-mode results: position, search depth reached and scores.
+    analysis: Option<(String, usize, Vec<Evaluation>)>,
+    /// The engine's verdict on a position, from Black's point of view.
+    evaluation: Option<(String, i32)>,
+    /// The engine's verdict on every move, once a finished game has been reviewed.
+    review: Option<Vec<Annotation>>,
+    animation: Option<Animation>,
+    now: Instant,
+    started: Instant,
+    /// How long the game took, once it is over.
+    finished: Option<Duration>,
+    pub(crate) clocks: Option<Clocks>,
+    /// The square picked with the arrow keys, once they have been used.
+    cursor: Option<usize>,
+    /// Moves taken back with undo, the latest last, for redo to put back.
+    redo: Vec<Vec<Ply>>,
+    /// The side that ran out of time, if that is how the game ended.
+    lost_on_time: Option<StoneColor>,
+    /// Looking through a finished game instead of at its results.
+    reviewing: bool,
+    /// The connection to the other player, in a LAN game.
+    lan: Option<net::Session>,
+    menu: Menu,
+    /// Where the settings and the game in progress are kept.
+    paths: Paths,
+}
+
+impl Game {
+    /// The mode being played: a LAN game is a two-player game whatever the settings say.
+    fn mode(&self) -> Mode {
+        match self.lan {
+            Some(_) => Mode::TwoPlayers,
+            None => self.menu.settings.mode,
+        }
+    }
+
+    fn movers(&self) -> (PlayerOrComputer, PlayerOrComputer) {
+        match self.mode() {
+            Mode::TwoPlayers | Mode::Analysis => (Player, Player),
+            Mode::VsComputer if self.game_board.turn == self.menu.settings.chosen_color => {
+                (Player, Computer)
+            }
+            Mode::VsComputer => (Computer, Player),
+        }
+    }
+
+    /// Clears the board for another game, keeping the menu settings.
+    fn new_game(&mut self) {
+        self.game_board = Board::new();
+        self.opening = Board::new();
+        self.history.clear();
+        self.viewing = None;
+        self.pass_notice = None;
+        self.hint = None;
+        self.analysis = None;
+        self.review = None;
+        self.animation = None;
+        self.finished = None;
+        self.clocks = None;
+        self.lost_on_time = None;
+        self.redo.clear();
+        self.reviewing = false;
+    }
+
+    /// Whether the position on screen is one the local player can move in.
+    fn players_turn(&self) -> bool {
+        self.menu.play_pressed
+            && self.viewing.is_none()
+            && self.game_board.win == GameOutcome::InProgress
+            && self.movers().0 == Player
+            && self
+                .lan
+                .as_ref()
+                .is_none_or(|lan| lan.can_move(&self.game_board))
+    }
+
+    /// The joining side of a LAN game, which follows the host's game instead of
+    /// starting its own.
+    fn guest(&self) -> bool {
+        self.lan.as_ref().is_some_and(|lan| !lan.is_host())
+    }
+
+    /// Whether this window only watches someone else's game.
+    fn spectating(&self) -> bool {
+        self.lan.as_ref().is_some_and(net::Session::is_spectator)
+    }
+
+    /// Asks the engine what it would play for the side to move, in the background.
+    fn hint(&self) -> Command<Message> {
+        let board = self.game_board.clone();
+        let position = board.to_position_string();
+        Command::perform(
+            on_own_thread(move || board.evaluate_moves(board.turn, Difficulty::Hard.depth())),
+            move |evaluations| Message::HintReady(position, evaluations),
+        )
+    }
+
+    /// Engine scores to draw on the board: the top few moves of a hint that is still
+    /// about the position on screen, or every move in analysis mode.
+    fn shown_evaluations(&self) -> Option<&[Evaluation]> {
+        if !self.players_turn() {
+            return None;
+        }
+        match (&self.hint, &self.analysis) {
+            (_, Some((position, _, evaluations)))
+                if self.mode() == Mode::Analysis
+                    && *position == self.game_board.to_position_string() =>
+            {
+                Some(evaluations)
+            }
+            (Some((position, evaluations)), _)
+                if *position == self.game_board.to_position_string() =>
+            {
+                Some(&evaluations[..evaluations.len().min(HINT_MOVES)])
+            }
+            _ => None,
+        }
+    }
+
+    /// What the evaluation bar should show for the position on screen, once it is known.
+    fn shown_evaluation(&self) -> Option<i32> {
+        let board = self.shown_board();
+        if board.win != GameOutcome::InProgress {
+            return Some(board.differential(StoneColor::Black));
+        }
+        match &self.evaluation {
+            Some((position, evaluation)) if *position == board.to_position_string() => {
+                Some(*evaluation)
+            }
+            _ => None,
+        }
+    }
+
+    fn run_review(&self) -> Command<Message> {
+        let (opening, history) = (self.opening.clone(), self.history.clone());
+        Command::perform(
+            on_own_thread(move || {
+                review_game_from(&opening, &history, REVIEW_DEPTH, MISTAKE_THRESHOLD)
+            }),
+            Message::ReviewReady,
+        )
+    }
+
+    /// Sets up a saved game, without starting it yet.
+    fn load_saved(&mut self, saved: save::SavedGame) {
+        self.opening = saved.opening;
+        self.game_board = saved.board;
+        self.history = saved.history;
+        self.menu.settings.chosen_color = saved.chosen_color;
+        self.menu.settings.mode = saved.mode;
+        self.menu.settings.difficulty = saved.difficulty;
+    }
+
+    fn start_game(&mut self, message: Message) {
+        self.menu.play_pressed = true;
+        self.started = Instant::now();
+        self.start_clocks(self.started);
+        if let Some(lan) = self.lan.as_mut().filter(|lan| lan.is_host()) {
+            lan.color = self.menu.settings.chosen_color;
+            lan.send_game(&self.opening, &self.history)
+        }
+        if self.movers().0 == Computer {
+            self.play(message)
+        }
+    }
+
+    /// The color the computer plays, if it plays at all.
+    fn computer_color(&self) -> Option<StoneColor> {
+        (self.mode() == Mode::VsComputer).then(|| self.menu.settings.chosen_color.reverse())
+    }
+
+    /// Hosts or joins a LAN game, played as a two-player game.
+    fn start_lan(&mut self, role: net::Role) {
+        match net::Session::open(role, self.menu.settings.chosen_color) {
+            Ok(session) => {
+                self.menu.lan_error = None;
+                self.lan = Some(session);
+                self.start_game(Message::MenuMessage(MenuItem::Play))
+            }
+            Err(error) => self.menu.lan_error = Some(error.to_string()),
+        }
+    }
+
+    /// Fresh clocks for the chosen time control, with the side to move's running.
+    /// Analysis is never timed, and neither are LAN games, whose two sides would
+    /// each keep their own time.
+    fn start_clocks(&mut self, now: Instant) {
+        self.clocks = match self.mode() {
+            _ if self.lan.is_some() => None,
+            Mode::Analysis => None,
+            _ => Clocks::new(self.menu.settings.time_control, self.game_board.turn, now),
+        };
+        self.lost_on_time = None;
+    }
+
+    /// Ends the game if the side to move has run out of time. Returns whether it has.
+    fn check_clocks(&mut self, now: Instant) -> bool {
+        if self.game_board.win != GameOutcome::InProgress {
+            return false;
+        }
+        match self.clocks.as_ref().and_then(|clocks| clocks.flagged(now)) {
+            Some(color) => {
+                self.game_board.win = GameOutcome::Win(color.reverse());
+                self.lost_on_time = Some(color);
+                self.finished = Some(self.started.elapsed());
+                self.autosave();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn play(&mut self, message: Message) {
+        let start = Instant::now();
+        if self.check_clocks(start) {
+            return;
+        }
+        let (mover_self, mover_other) = self.movers();
+        let computer = self.computer_color();
+        let budget = self
+            .clocks
+            .as_ref()
+            .zip(computer)
+            .map(|(clocks, color)| clocks.budget(color, start));
+        let plies = self.history.len();
+        let before = self.game_board.board.clone();
+        self.game_board.colored_move_recorded(
+            message,
+            mover_self,
+            mover_other,
+            self.game_board.turn,
+            Search {
+                difficulty: self.menu.settings.difficulty,
+                budget,
+            },
+            &mut self.history,
+        );
+        if self.history.len() != plies {
+            self.redo.clear();
+            if let Some(clocks) = &mut self.clocks {
+                // A human's move took from when their clock started to the click; the
+                // computer's replies share the time spent in the search.
+                let done = Instant::now();
+                let moves: Vec<StoneColor> = self.history[plies..]
+                    .iter()
+                    .filter(|ply| !ply.is_pass())
+                    .map(|ply| ply.color)
+                    .collect();
+                let searches = moves
+                    .iter()
+                    .filter(|&&color| Some(color) == computer)
+                    .count();
+                for color in moves {
+                    let elapsed = match Some(color) == computer {
+                        true => (done - start) / searches as u32,
+                        false => clocks.elapsed(start),
+                    };
+                    clocks.spend(color, elapsed)
+                }
+                clocks.start(self.game_board.turn, done)
+            }
+            if let Some(lan) = &self.lan {
+                lan.played(plies, &self.history, &self.game_board)
+            }
+            self.pass_notice = self.history[plies..]
+                .iter()
+                .rfind(|ply| ply.is_pass())
+                .map(|ply| ply.color);
+            if self.game_board.win != GameOutcome::InProgress && self.finished.is_none() {
+                self.finished = Some(self.started.elapsed())
+            }
+            if self.menu.settings.animations {
+                self.now = Instant::now();
+                self.animation = Some(Animation::new(
+                    before,
+                    &self.history[plies..],
+                    self.menu.settings.animation_speed,
+                    self.now,
+                ));
+            }
+            self.autosave()
+        }
+    }
+
+    /// The position on screen: the one being browsed in the move list, or the live one.
+    fn shown_board(&self) -> &Board {
+        match &self.viewing {
+            Some((_, board)) => board,
+            None => &self.game_board,
+        }
+    }
+
+    fn shown_history(&self) -> &[Ply] {
+        match &self.viewing {
+            Some((plies, _)) => &self.history[..*plies],
+            None => &self.history,
+        }
+    }
+
+    /// The name typed in for `color`'s player, or the color in the chosen language.
+    fn player_name(&self, color: StoneColor) -> &str {
+        match self.menu.settings.name(color) {
+            "" => self.menu.catalog.color(color),
+            name => name,
+        }
+    }
+
+    /// The theme picked in the settings, or the first one if it is gone.
+    fn game_theme(&self) -> &GameTheme {
+        let themes = &self.menu.themes;
+        themes
+            .iter()
+            .find(|theme| theme.name == self.menu.settings.theme)
+            .unwrap_or(&themes[0])
+    }
+
+    /// Carries on from the end of `history`, after moves were taken back or replayed.
+    fn replace_history(&mut self, history: Vec<Ply>) {
+        self.game_board = self.opening.replay(&history);
+        self.history = history;
+        self.viewing = None;
+        self.pass_notice = None;
+        self.hint = None;
+        self.review = None;
+        self.reviewing = false;
+        self.animation = None;
+        // Taking moves back doesn't give the time back: the clocks keep what they had
+        // and the side now to move has theirs running.
+        if let Some(clocks) = &mut self.clocks {
+            clocks.start(self.game_board.turn, Instant::now())
+        }
+        self.lost_on_time = None;
+        self.finished =
+            (self.game_board.win != GameOutcome::InProgress).then(|| self.started.elapsed());
+        self.autosave();
+    }
+
+    /// Takes back the last move made by hand, along with the computer's replies to it.
+    fn undo(&mut self) {
+        let computer = self.computer_color();
+        let Some(taken) = self
+            .history
+            .iter()
+            .rposition(|ply| !ply.is_pass() && Some(ply.color) != computer)
+        else {
+            return;
+        };
+        let mut history = self.history.clone();
+        self.redo.push(history.split_off(taken));
+        self.replace_history(history)
+    }
+
+    fn redo(&mut self) {
+        if let Some(plies) = self.redo.pop() {
+            let mut history = self.history.clone();
+            history.extend(plies);
+            self.replace_history(history)
+        }
+    }
+
+    fn store_settings(&self) {
+        if let Err(error) = settings::store(&self.paths, &self.menu.settings) {
+            eprintln!("Couldn't save the settings: {error}")
+        }
+    }
+
+    /// LAN games aren't saved: there would be nobody to continue them with.
+    fn autosave(&self) {
+        if self.lan.is_some() {
+            return;
+        }
+        if self.game_board.win != GameOutcome::InProgress {
+            save::remove(&self.paths);
+            return;
+        }
+        let saved = save::SavedGame {
+            opening: self.opening.clone(),
+            board: self.game_board.clone(),
+            history: self.history.clone(),
+            chosen_color: self.menu.settings.chosen_color,
+            mode: self.mode(),
+            difficulty: self.menu.settings.difficulty,
+        };
+        if let Err(error) = save::store(&self.paths, &saved) {
+            eprintln!("Could not save the game: {error}")
+        }
+    }
+}
+
+/// How often running clocks are redrawn. Shorter than a second so the display doesn't
+/// skip one.
+const CLOCK_REDRAW: Duration = Duration::from_millis(200);
+
+/// Deepest search analysis mode goes to before it stops refining: no deeper than the
+/// computer at its hardest, since a search at one depth can't be stopped partway.
+const ANALYSIS_DEPTH: usize = Difficulty::Hard.depth();
+
+/// Scores every legal move of `board` one depth at a time, reporting after each one.
+/// Keyed by position, so moving on drops the search for the old one. The search
+/// deepens on a thread of its own, which gives up before the next depth once nobody
+/// is listening.
+fn analysis(board: Board) -> Subscription<Message> {
+    let position = board.to_position_string();
+    subscription::channel(position.clone(), 1, move |mut output| async move {
+        let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+        thread::spawn(move || {
+            for depth in 2..=ANALYSIS_DEPTH {
+                if sender.is_closed() {
+                    break;
+                }
+                let evaluations = board.evaluate_moves(board.turn, depth);
+                let _ = sender.unbounded_send((depth, evaluations));
+            }
+        });
+        while let Some((depth, evaluations)) = receiver.next().await {
+            let _ = output
+                .send(Message::AnalysisUpdate(
+                    position.clone(),
+                    depth,
+                    evaluations,
+                ))
+                .await;
+        }
+        future::pending().await
+    })
+}
+
+/// Arrow keys move the cursor and Enter or Space plays on it; `u`, `r`, `n` and `h`
+/// undo, redo, start a new game and ask for a hint.
+fn key_press(key: Key, modifiers: keyboard::Modifiers) -> Option<Message> {
+    if modifiers.command() || modifiers.alt() {
+        return None;
+    }
+    match key.as_ref() {
+        Key::Named(Named::ArrowUp) => Some(Message::MoveCursor(-1, 0)),
+        Key::Named(Named::ArrowDown) => Some(Message::MoveCursor(1, 0)),
+        Key::Named(Named::ArrowLeft) => Some(Message::MoveCursor(0, -1)),
+        Key::Named(Named::ArrowRight) => Some(Message::MoveCursor(0, 1)),
+        Key::Named(Named::Enter | Named::Space) => Some(Message::PlayCursor),
+        Key::Character("u") => Some(Message::Undo),
+        Key::Character("r") => Some(Message::Redo),
+        Key::Character("n") => Some(Message::NewGame),
+        Key::Character("h") => Some(Message::Hint),
+        _ => None,
+    }
+}
+
+/// Search depth behind the evaluation bar.
+const EVALUATION_DEPTH: usize = 4;
+
+/// The disc differential the side to move in `board` can expect, turned around to
+/// Black's point of view. Keyed by position like [`analysis`].
+fn evaluation(board: Board) -> Subscription<Message> {
+    let position = board.to_position_string();
+    subscription::channel(
+        ("evaluation", position.clone()),
+        1,
+        move |mut output| async move {
+            let black_evaluation = on_own_thread(move || {
+                let best = board
+                    .evaluate_moves(board.turn, EVALUATION_DEPTH)
+                    .first()
+                    .map_or(board.differential(board.turn), |evaluation| {
+                        evaluation.differential
+                    });
+                match board.turn {
+                    StoneColor::Black => best,
+                    StoneColor::White => -best,
+                }
+            })
+            .await;
+            let _ = output
+                .send(Message::EvaluationUpdate(position, black_evaluation))
+                .await;
+            future::pending().await
+        },
+    )
+}
+
+/// Runs `work` on a thread of its own and waits for it there, so a search doesn't
+/// tie up the executor that also runs the clocks and the LAN connection.
+async fn on_own_thread<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+    receiver.await.expect("the search thread panicked")
+}
+
+/// What the LAN connection hears, passed on from a thread of its own so that waiting
+/// for the other side doesn't hold up the window.
+fn network(role: net::Role, events: net::Events) -> Subscription<Message> {
+    subscription::channel(role, 16, move |mut output| async move {
+        let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+        thread::spawn(move || {
+            while let Some(event) = events.next() {
+                if sender.unbounded_send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        while let Some(event) = receiver.next().await {
+            let _ = output.send(Message::Network(event)).await;
+        }
+        future::pending().await
+    })
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::with_paths(Paths::user())
+    }
+}
+
+impl Game {
+    /// The menu, with the settings, themes and saved game kept in `paths`.
+    pub fn with_paths(paths: Paths) -> Game {
+        Game {
+            game_board: { Board::new() },
+            opening: Board::new(),
+            history: Vec::new(),
+            viewing: None,
+            pass_notice: None,
+            hint: None,
+            analysis: None,
+            evaluation: None,
+            review: None,
+            animation: None,
+            now: Instant::now(),
+            started: Instant::now(),
+            finished: None,
+            clocks: None,
+            lost_on_time: None,
+            cursor: None,
+            redo: Vec::new(),
+            reviewing: false,
+            lan: None,
+            menu: Menu::new(&paths),
+            paths,
+        }
+    }
+
+    /// What [`Application::new`] does, with the game's files in `paths`.
+    pub(crate) fn open(options: cli::Options, paths: Paths) -> (Game, Command<Message>) {
+        let mut game = Game::with_paths(paths);
+        let starts_game = options.starts_game();
+        if let Some(saved) = options.game {
+            game.load_saved(saved)
+        }
+        if let Some(board) = options.position {
+            game.opening = board.clone();
+            game.game_board = board;
+        }
+        let settings = &mut game.menu.settings;
+        if let Some(mode) = options.mode {
+            settings.mode = mode
+        }
+        if let Some(color) = options.color {
+            settings.chosen_color = color
+        }
+        if let Some(difficulty) = options.difficulty {
+            settings.difficulty = difficulty
+        }
+        if let Some(display_size) = options.display_size {
+            settings.display_size = display_size
+        }
+        if let Some(role) = options.lan {
+            game.start_lan(role)
+        } else if starts_game {
+            game.start_game(Message::MenuMessage(MenuItem::Play))
+        }
+        let command = match options.fullscreen {
+            true => window::change_mode(window::Id::MAIN, window::Mode::Fullscreen),
+            false => Command::none(),
+        };
+        (game, command)
+    }
+}
+
+impl Application for Game {
+    type Executor = executor::Default;
+    type Message = Message;
+    type Theme = Theme;
+    type Flags = cli::Options;
+
+    fn new(options: cli::Options) -> (Self, Command<Message>) {
+        Game::open(options, Paths::user())
+    }
+
+    fn title(&self) -> String {
+        self.menu.catalog.get("title").to_owned()
+    }
+
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::MenuMessage(menu_item) => match menu_item {
+                MenuItem::ChooseColor(color) => self.menu.settings.chosen_color = color,
+                MenuItem::ChooseMode(mode) => self.menu.settings.mode = mode,
+                MenuItem::ChooseDifficulty(difficulty) => {
+                    self.menu.settings.difficulty = difficulty
+                }
+                MenuItem::ToggleLegalMoves => {
+                    self.menu.settings.show_legal_moves = !self.menu.settings.show_legal_moves
+                }
+                MenuItem::ToggleFlipCounts => {
+                    self.menu.settings.show_flip_counts = !self.menu.settings.show_flip_counts
+                }
+                MenuItem::ToggleLastMove => {
+                    self.menu.settings.show_last_move = !self.menu.settings.show_last_move
+                }
+                MenuItem::ToggleEvaluation => {
+                    self.menu.settings.show_evaluation = !self.menu.settings.show_evaluation
+                }
+                MenuItem::ToggleAnimations => {
+                    self.menu.settings.animations = !self.menu.settings.animations
+                }
+                MenuItem::ChooseAnimationSpeed(speed) => self.menu.settings.animation_speed = speed,
+                MenuItem::ChooseTimeControl(control) => self.menu.settings.time_control = control,
+                MenuItem::ChooseDisplaySize(size) => self.menu.settings.display_size = size,
+                MenuItem::NextTheme => {
+                    let themes = &self.menu.themes;
+                    let current = themes
+                        .iter()
+                        .position(|theme| theme.name == self.menu.settings.theme);
+                    let next = current.map_or(0, |current| (current + 1) % themes.len());
+                    self.menu.settings.theme = themes[next].name.clone()
+                }
+                MenuItem::ToggleCoordinates => {
+                    self.menu.settings.show_coordinates = !self.menu.settings.show_coordinates
+                }
+                MenuItem::ToggleAccessibility => {
+                    self.menu.settings.accessibility = !self.menu.settings.accessibility
+                }
+                MenuItem::ToggleDiscSymbols => {
+                    self.menu.settings.disc_symbols = !self.menu.settings.disc_symbols
+                }
+                MenuItem::ChooseUiScale(scale) => self.menu.settings.ui_scale = scale,
+                MenuItem::ChooseLanguage(language) => {
+                    self.menu.settings.language = language;
+                    self.menu.catalog = Catalog::new(language, &self.paths)
+                }
+                MenuItem::OpenSettings => self.menu.settings_open = true,
+                MenuItem::CloseSettings => {
+                    self.menu.settings_open = false;
+                    self.store_settings()
+                }
+                MenuItem::Play => {
+                    self.store_settings();
+                    self.start_game(message)
+                }
+                MenuItem::HostLan => self.start_lan(net::Role::Host(net::DEFAULT_PORT)),
+                MenuItem::JoinLan if self.menu.lan_address.trim().is_empty() => {}
+                MenuItem::JoinLan => self.start_lan(net::Role::Join(net::with_default_port(
+                    self.menu.lan_address.trim(),
+                ))),
+                MenuItem::WatchLan if self.menu.lan_address.trim().is_empty() => {}
+                MenuItem::WatchLan => self.start_lan(net::Role::Watch(net::with_default_port(
+                    self.menu.lan_address.trim(),
+                ))),
+                MenuItem::Continue => match save::load(&self.paths) {
+                    Some(saved) => {
+                        self.load_saved(saved);
+                        self.start_game(message)
+                    }
+                    None => self.menu.saved_game = false,
+                },
+            },
+            Message::GameOverMessage(GameOverItem::Rematch | GameOverItem::SwapColors)
+                if self.spectating() => {}
+            Message::GameOverMessage(GameOverItem::Rematch | GameOverItem::SwapColors)
+                if self.guest() =>
+            {
+                if let Some(lan) = &self.lan {
+                    lan.rematch()
+                }
+            }
+            Message::GameOverMessage(item) => match item {
+                GameOverItem::Rematch => {
+                    self.new_game();
+                    self.start_game(message)
+                }
+                GameOverItem::SwapColors => {
+                    self.new_game();
+                    self.menu.settings.chosen_color = self.menu.settings.chosen_color.reverse();
+                    self.start_game(message)
+                }
+                GameOverItem::Review => {
+                    self.reviewing = true;
+                    if self.review.is_none() {
+                        return self.run_review();
+                    }
+                }
+                GameOverItem::Results => {
+                    self.reviewing = false;
+                    self.viewing = None
+                }
+                GameOverItem::BackToMenu => {
+                    self.lan = None;
+                    self.new_game();
+                    self.menu.play_pressed = false;
+                    self.menu.saved_game = save::exists(&self.paths);
+                }
+            },
+            Message::Reset => {
+                save::remove(&self.paths);
+                *self = Game::with_paths(self.paths.clone());
+            }
+            Message::ShowPly(plies) => {
+                self.viewing = plies
+                    .filter(|&plies| plies < self.history.len())
+                    .map(|plies| (plies, self.opening.replay(&self.history[..plies])))
+            }
+            Message::ResumeFromHere if self.lan.is_some() => {}
+            Message::ResumeFromHere => {
+                if let Some((plies, _)) = self.viewing {
+                    let history = self.history[..plies].to_vec();
+                    self.redo.clear();
+                    self.replace_history(history);
+                    if self.game_board.win == GameOutcome::InProgress && self.movers().0 == Computer
+                    {
+                        self.play(message)
+                    }
+                }
+            }
+            Message::Hint if self.players_turn() => return self.hint(),
+            Message::Hint => {}
+            Message::HintReady(position, evaluations) => self.hint = Some((position, evaluations)),
+            Message::ReviewReady(annotations) => self.review = Some(annotations),
+            Message::AnalysisUpdate(position, depth, evaluations) => {
+                self.analysis = Some((position, depth, evaluations))
+            }
+            Message::EvaluationUpdate(position, evaluation) => {
+                self.evaluation = Some((position, evaluation))
+            }
+            Message::MoveCursor(rows, columns) => {
+                let (row, column) = match self.cursor {
+                    Some(id) => {
+                        let (row, column) = index_to_pair(id);
+                        (
+                            row.saturating_add_signed(rows).min(HEIGHT - 1),
+                            column.saturating_add_signed(columns).min(WIDTH - 1),
+                        )
+                    }
+                    // The first key press only brings the cursor up.
+                    None => (HEIGHT / 2 - 1, WIDTH / 2 - 1),
+                };
+                self.cursor = Some(pair_to_index(row, column))
+            }
+            Message::PlayCursor => {
+                if let Some(id) = self.cursor.filter(|_| self.players_turn()) {
+                    let (row, column) = index_to_pair(id);
+                    self.play(Message::EmptyPressed(row, column))
+                }
+            }
+            Message::Undo | Message::Redo
+                if !self.menu.play_pressed || self.viewing.is_some() || self.lan.is_some() => {}
+            Message::Undo => self.undo(),
+            Message::Redo => self.redo(),
+            Message::NewGame if !self.menu.play_pressed || self.guest() => {}
+            Message::NewGame => {
+                self.new_game();
+                self.start_game(message)
+            }
+            Message::LanAddress(address) => self.menu.lan_address = address,
+            Message::Network(event) => {
+                let Some(lan) = &mut self.lan else {
+                    return Command::none();
+                };
+                match lan.handle(event, &self.opening, &self.history, &self.game_board) {
+                    Some(net::Update::Play(square)) => {
+                        let (row, column) = index_to_pair(square);
+                        self.play(Message::EmptyPressed(row, column))
+                    }
+                    Some(net::Update::Replace { opening, history }) => {
+                        self.opening = opening;
+                        self.redo.clear();
+                        self.replace_history(history)
+                    }
+                    Some(net::Update::Rematch)
+                        if self.game_board.win != GameOutcome::InProgress =>
+                    {
+                        return self.update(Message::GameOverMessage(GameOverItem::SwapColors))
+                    }
+                    Some(net::Update::Rematch) => {}
+                    None => {}
+                }
+            }
+            Message::PlayerName(StoneColor::Black, name) => self.menu.settings.black_name = name,
+            Message::PlayerName(StoneColor::White, name) => self.menu.settings.white_name = name,
+            // Moves from the other side of a LAN game come in through `play` directly.
+            Message::EmptyPressed(..) if !self.players_turn() => {}
+            Message::Tick(now) => {
+                self.now = now;
+                self.check_clocks(now);
+                if self
+                    .animation
+                    .as_ref()
+                    .is_some_and(|animation| animation.is_finished(now))
+                {
+                    self.animation = None
+                }
+            }
+            message => self.play(message),
+        }
+        Command::none()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let clocks_running = self.menu.play_pressed
+            && self.clocks.is_some()
+            && self.game_board.win == GameOutcome::InProgress;
+        // Animations need every frame, clocks only change once a second.
+        let animation = match (self.animation.is_some(), clocks_running) {
+            (true, _) => window::frames().map(Message::Tick),
+            (false, true) => time::every(CLOCK_REDRAW).map(Message::Tick),
+            (false, false) => Subscription::none(),
+        };
+        let analysis =
+            match self.menu.play_pressed && self.mode() == Mode::Analysis && self.players_turn() {
+                true => analysis(self.game_board.clone()),
+                false => Subscription::none(),
+            };
+        let shown = self.shown_board();
+        let evaluation = match self.menu.play_pressed
+            && self.menu.settings.show_evaluation
+            && shown.win == GameOutcome::InProgress
+        {
+            true => evaluation(shown.clone()),
+            false => Subscription::none(),
+        };
+        let network = match &self.lan {
+            Some(lan) => network(lan.role().clone(), lan.events()),
+            None => Subscription::none(),
+        };
+        Subscription::batch([
+            animation,
+            analysis,
+            evaluation,
+            network,
+            keyboard::on_key_press(key_press),
+        ])
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.menu.settings.ui_scale as f64 / 100.0
+    }
+
+    fn theme(&self) -> Theme {
+        self.game_theme().iced_theme()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let game_over = self.game_board.win != GameOutcome::InProgress
+            && self.animation.is_none()
+            && !self.reviewing;
+        match (self.menu.play_pressed, game_over) {
+            (true, true) => game_over_screen(self),
+            (true, false) => playfield(self),
+            (false, _) if self.menu.settings_open => settings_page(self),
+            (false, _) => menu(self),
+        }
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .center_x()
+        .center_y()
+        .align_x(Horizontal::Center)
+        .align_y(Vertical::Center)
+        .into()
+    }
+}
+
+/// Serialized as just its settings: themes and strings are loaded again on the way in.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "settings::Settings", into = "settings::Settings")
+)]
+pub struct Menu {
+    settings: settings::Settings,
+    /// The built-in themes and any the user added, to pick from by name.
+    themes: Vec<GameTheme>,
+    /// Interface strings in `settings.language`.
+    catalog: Catalog,
+    settings_open: bool,
+    play_pressed: bool,
+    saved_game: bool,
+    /// The host to join, as typed in.
+    lan_address: String,
+    /// Why hosting didn't work, the last time it was tried.
+    lan_error: Option<String>,
+}
+impl Menu {
+    fn new(paths: &Paths) -> Self {
+        Menu::with_settings(settings::load(paths), paths)
+    }
+
+    fn with_settings(settings: settings::Settings, paths: &Paths) -> Self {
+        Menu {
+            catalog: Catalog::new(settings.language, paths),
+            settings,
+            themes: themes::load(paths),
+            settings_open: false,
+            play_pressed: false,
+            saved_game: save::exists(paths),
+            lan_address: String::new(),
+            lan_error: None,
+        }
+    }
+}
+impl From<settings::Settings> for Menu {
+    fn from(settings: settings::Settings) -> Self {
+        Menu::with_settings(settings, &Paths::user())
+    }
+}
+impl From<Menu> for settings::Settings {
+    fn from(menu: Menu) -> Self {
+        menu.settings
+    }
+}
+
+fn menu_button<'a>(label: &str, item: MenuItem) -> Button<'a, Message> {
+    button(
+        text(label)
+            .horizontal_alignment(Horizontal::Center)
+            .vertical_alignment(Vertical::Center),
+    )
+    .on_press(Message::MenuMessage(item))
+    .style(theme::Button::Secondary)
+    .height(Length::Fixed(50.0))
+    .width(Length::Fixed(210.0))
+}
+
+fn mode_button(mode: Mode, catalog: &Catalog) -> Button<'static, Message> {
+    let (label, next) = match mode {
+        Mode::VsComputer => ("vs_computer", Mode::TwoPlayers),
+        Mode::TwoPlayers => ("two_players", Mode::Analysis),
+        Mode::Analysis => ("analysis", Mode::VsComputer),
+    };
+    menu_button(catalog.get(label), MenuItem::ChooseMode(next))
+}
+
+fn difficulty_button(difficulty: Difficulty, catalog: &Catalog) -> Button<'static, Message> {
+    let (label, next) = match difficulty {
+        Difficulty::Easy => ("easy", Difficulty::Medium),
+        Difficulty::Medium => ("medium", Difficulty::Hard),
+        Difficulty::Hard => ("hard", Difficulty::Easy),
+    };
+    menu_button(catalog.get(label), MenuItem::ChooseDifficulty(next))
+}
+
+fn menu(game: &Game) -> Container<'_, Message> {
+    let settings = &game.menu.settings;
+    let catalog = &game.menu.catalog;
+    let outline = settings
+        .accessibility
+        .then_some(game.game_theme().palette.text);
+    let mut options = column![mode_button(settings.mode, catalog)]
+        .spacing(10)
+        .align_items(Alignment::Center);
+    if settings.mode == Mode::VsComputer {
+        options = options.push(difficulty_button(settings.difficulty, catalog))
+    }
+    options = options.push(menu_button(catalog.get("settings"), MenuItem::OpenSettings));
+    if game.menu.saved_game {
+        options = options.push(menu_button(catalog.get("continue"), MenuItem::Continue))
+    }
+    options = options
+        .push(menu_button(catalog.get("host_lan"), MenuItem::HostLan))
+        .push(
+            text_input(catalog.get("lan_address"), &game.menu.lan_address)
+                .on_input(Message::LanAddress)
+                .on_submit(Message::MenuMessage(MenuItem::JoinLan))
+                .width(Length::Fixed(210.0)),
+        )
+        .push(menu_button(catalog.get("join_lan"), MenuItem::JoinLan))
+        .push(menu_button(catalog.get("watch_lan"), MenuItem::WatchLan));
+    if let Some(error) = &game.menu.lan_error {
+        options = options.push(text(catalog.fill("lan_host_failed", &[("error", error)])))
+    }
+    container(
+        column![
+            row![
+                button(text(catalog.get("play")))
+                    .on_press(Message::MenuMessage(MenuItem::Play))
+                    .style(theme::Button::Text)
+                    .height(Length::Fixed(100.0))
+                    .width(Length::Fixed(100.0)),
+                match settings.chosen_color {
+                    StoneColor::Black =>
+                        button(circle(40.0, game.game_theme().black).outline(outline)).on_press(
+                            Message::MenuMessage(MenuItem::ChooseColor(StoneColor::White))
+                        ),
+                    StoneColor::White =>
+                        button(circle(40.0, game.game_theme().white).outline(outline)).on_press(
+                            Message::MenuMessage(MenuItem::ChooseColor(StoneColor::Black))
+                        ),
+                }
+                .style(theme::Button::Positive)
+                .padding(10)
+                .height(Length::Fixed(100.0))
+                .width(Length::Fixed(100.0))
+            ]
+            .spacing(10),
+            options
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .center_x()
+    .center_y()
+}
+
+/// Every option, in three columns. Changes are written to the config file on "Done".
+fn settings_page(game: &Game) -> Container<'_, Message> {
+    let settings = &game.menu.settings;
+    let catalog = &game.menu.catalog;
+    let shown = |option: &str, on: bool, item: MenuItem| {
+        let state = match on {
+            true => catalog.get("shown"),
+            false => catalog.get("hidden"),
+        };
+        menu_button(
+            &catalog.fill(
+                "option_state",
+                &[("option", &catalog.get(option)), ("state", &state)],
+            ),
+            item,
+        )
+    };
+    let heading = |key: &'static str| text(catalog.get(key)).size(24);
+
+    let mut game_options = column![heading("heading_game"), mode_button(settings.mode, catalog)]
+        .spacing(10)
+        .align_items(Alignment::Center);
+    if settings.mode == Mode::VsComputer {
+        game_options = game_options.push(difficulty_button(settings.difficulty, catalog))
+    }
+    let control = settings.time_control;
+    let clock = match control {
+        TimeControl::Unlimited => "clock_none",
+        TimeControl::SuddenDeath(_) => "clock_sudden_death",
+        TimeControl::Increment(..) => "clock_increment",
+        TimeControl::ByoYomi(..) => "clock_byo_yomi",
+    };
+    game_options = game_options.push(menu_button(
+        &catalog.fill("clock", &[("kind", &catalog.get(clock))]),
+        MenuItem::ChooseTimeControl(control.next()),
+    ));
+    if control != TimeControl::Unlimited {
+        game_options = game_options.push(menu_button(
+            &catalog.fill(
+                "main_time",
+                &[("minutes", &(control.main().as_secs() / 60))],
+            ),
+            MenuItem::ChooseTimeControl(control.next_main()),
+        ))
+    }
+    let extra = match control {
+        TimeControl::Increment(..) => Some("increment"),
+        TimeControl::ByoYomi(..) => Some("byo_yomi_period"),
+        _ => None,
+    };
+    if let Some(extra) = extra {
+        game_options = game_options.push(menu_button(
+            &catalog.fill(extra, &[("seconds", &control.extra().as_secs())]),
+            MenuItem::ChooseTimeControl(control.next_extra()),
+        ))
+    }
+
+    let (display_size, next_size) = match settings.display_size {
+        DisplaySize::Small => ("display_size_small", DisplaySize::Medium),
+        DisplaySize::Medium => ("display_size_medium", DisplaySize::Large),
+        DisplaySize::Large => ("display_size_large", DisplaySize::Fit),
+        DisplaySize::Fit => ("display_size_fit", DisplaySize::Small),
+    };
+    let display_size = menu_button(
+        catalog.get(display_size),
+        MenuItem::ChooseDisplaySize(next_size),
+    );
+    let theme_choice = menu_button(
+        &catalog.fill("theme", &[("name", &game.game_theme().name)]),
+        MenuItem::NextTheme,
+    );
+    let mut board_options = column![
+        heading("heading_board"),
+        display_size,
+        theme_choice,
+        shown(
+            "coordinates",
+            settings.show_coordinates,
+            MenuItem::ToggleCoordinates
+        ),
+        shown(
+            "legal_moves",
+            settings.show_legal_moves,
+            MenuItem::ToggleLegalMoves
+        ),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+    if settings.show_legal_moves {
+        board_options = board_options.push(shown(
+            "flip_counts",
+            settings.show_flip_counts,
+            MenuItem::ToggleFlipCounts,
+        ))
+    }
+    board_options = board_options
+        .push(shown(
+            "last_move",
+            settings.show_last_move,
+            MenuItem::ToggleLastMove,
+        ))
+        .push(shown(
+            "evaluation_bar",
+            settings.show_evaluation,
+            MenuItem::ToggleEvaluation,
+        ));
+
+    let animations = match settings.animations {
+        true => menu_button(catalog.get("animations_on"), MenuItem::ToggleAnimations),
+        false => menu_button(catalog.get("animations_off"), MenuItem::ToggleAnimations),
+    };
+    let (animation_speed, next_speed) = match settings.animation_speed {
+        AnimationSpeed::Slow => ("animation_speed_slow", AnimationSpeed::Normal),
+        AnimationSpeed::Normal => ("animation_speed_normal", AnimationSpeed::Fast),
+        AnimationSpeed::Fast => ("animation_speed_fast", AnimationSpeed::Slow),
+    };
+    let animation_speed = menu_button(
+        catalog.get(animation_speed),
+        MenuItem::ChooseAnimationSpeed(next_speed),
+    );
+    let mut other_options = column![heading("heading_animations"), animations]
+        .spacing(10)
+        .align_items(Alignment::Center);
+    if settings.animations {
+        other_options = other_options.push(animation_speed)
+    }
+    let name = |color: StoneColor| {
+        text_input(catalog.color(color), settings.name(color))
+            .on_input(move |name| Message::PlayerName(color, name))
+            .width(Length::Fixed(210.0))
+    };
+    other_options = other_options
+        .push(heading("heading_players"))
+        .push(name(StoneColor::Black))
+        .push(name(StoneColor::White));
+    let accessibility = match settings.accessibility {
+        true => menu_button(
+            catalog.get("accessibility_on"),
+            MenuItem::ToggleAccessibility,
+        ),
+        false => menu_button(
+            catalog.get("accessibility_off"),
+            MenuItem::ToggleAccessibility,
+        ),
+    };
+    let next_scale = settings::UI_SCALES
+        .iter()
+        .copied()
+        .find(|&scale| scale > settings.ui_scale)
+        .unwrap_or(settings::UI_SCALES[0]);
+    other_options = other_options
+        .push(heading("heading_accessibility"))
+        .push(accessibility)
+        .push(shown(
+            "disc_symbols",
+            settings.disc_symbols,
+            MenuItem::ToggleDiscSymbols,
+        ))
+        .push(menu_button(
+            &catalog.fill("ui_size", &[("percent", &settings.ui_scale)]),
+            MenuItem::ChooseUiScale(next_scale),
+        ));
+    game_options = game_options.push(menu_button(
+        &catalog.fill("language", &[("name", &settings.language.native_name())]),
+        MenuItem::ChooseLanguage(settings.language.next()),
+    ));
+
+    container(
+        column![
+            row![game_options, board_options, other_options].spacing(30),
+            button(text(catalog.get("done")).horizontal_alignment(Horizontal::Center))
+                .on_press(Message::MenuMessage(MenuItem::CloseSettings))
+                .width(Length::Fixed(210.0)),
+        ]
+        .spacing(30)
+        .align_items(Alignment::Center),
+    )
+    .center_x()
+    .center_y()
+}
+
+/// Numbered plies in a–h/1–8 notation. Clicking one shows the position after it.
+fn move_list(game: &Game) -> Column<'_, Message> {
+    let catalog = &game.menu.catalog;
+    let shown = match &game.viewing {
+        Some((plies, _)) => *plies,
+        None => game.history.len(),
+    };
+    let entry = |label: String, plies: usize| {
+        button(text(label))
+            .on_press(Message::ShowPly(Some(plies)))
+            .style(match plies == shown {
+                true => theme::Button::Primary,
+                false => theme::Button::Text,
+            })
+            .width(Length::Fill)
+    };
+    let mut list = column![entry(catalog.get("start").to_owned(), 0)].spacing(2);
+    let mut number = 0;
+    for (n, ply) in game.history.iter().enumerate() {
+        list = match ply.square {
+            Some(square) => {
+                number += 1;
+                list.push(entry(
+                    catalog.fill(
+                        "move_entry",
+                        &[
+                            ("number", &number),
+                            ("color", &catalog.color(ply.color)),
+                            ("square", &square_name(square)),
+                        ],
+                    ),
+                    n + 1,
+                ))
+            }
+            None => list.push(
+                text(catalog.fill("passes", &[("color", &catalog.color(ply.color))])).size(14),
+            ),
+        }
+    }
+    let mut panel = column![scrollable(list).height(Length::Fill)].spacing(10);
+    if game.viewing.is_some() {
+        panel = panel.push(
+            button(text(catalog.get("back_to_game")))
+                .on_press(Message::ShowPly(None))
+                .style(theme::Button::Secondary),
+        );
+        if game.lan.is_none() {
+            panel = panel.push(
+                button(text(catalog.get("resume_from_here")))
+                    .on_press(Message::ResumeFromHere)
+                    .style(theme::Button::Positive),
+            )
+        }
+    }
+    panel.width(Length::Fixed(180.0))
+}
+
+fn game_over_screen(game: &Game) -> Container<'_, Message> {
+    let catalog = &game.menu.catalog;
+    let board = &game.game_board;
+    let (black, white) = (board.black_count, board.white_count);
+    let headline = match board.win {
+        GameOutcome::Win(color) if game.lost_on_time.is_some() => {
+            catalog.fill("wins_on_time", &[("name", &game.player_name(color))])
+        }
+        GameOutcome::Win(color) => catalog.fill("wins", &[("name", &game.player_name(color))]),
+        _ => catalog.get("draw").to_owned(),
+    };
+    let ahead_by = |color: StoneColor, discs: usize| {
+        catalog.fill(
+            "ahead_by",
+            &[("color", &catalog.color(color)), ("discs", &discs)],
+        )
+    };
+    let differential = match black.cmp(&white) {
+        Ordering::Greater => ahead_by(StoneColor::Black, black - white),
+        Ordering::Less => ahead_by(StoneColor::White, white - black),
+        Ordering::Equal => catalog.get("even").to_owned(),
+    };
+    let moves = game.history.iter().filter(|ply| !ply.is_pass()).count();
+    let passes = game.history.len() - moves;
+    let seconds = game.finished.unwrap_or_default().as_secs();
+    let stat = |label: &'static str, value: String| {
+        row![
+            text(catalog.get(label)).width(Length::Fixed(140.0)),
+            text(value).width(Length::Fixed(140.0))
+        ]
+    };
+    let action = |label: &'static str, item: GameOverItem| {
+        button(text(catalog.get(label)).horizontal_alignment(Horizontal::Center))
+            .on_press(Message::GameOverMessage(item))
+            .style(theme::Button::Secondary)
+            .width(Length::Fixed(140.0))
+    };
+    container(
+        column![
+            text(headline).size(40),
+            stat(
+                "final_score",
+                catalog.fill(
+                    "final_score_value",
+                    &[
+                        ("black", &catalog.color(StoneColor::Black)),
+                        ("black_discs", &black),
+                        ("white_discs", &white),
+                        ("white", &catalog.color(StoneColor::White)),
+                    ]
+                )
+            ),
+            stat("disc_differential", differential),
+            stat(
+                "moves",
+                catalog.fill("moves_value", &[("moves", &moves), ("passes", &passes)])
+            ),
+            stat("time", format!("{}:{:02}", seconds / 60, seconds % 60)),
+            // Only the host starts the next LAN game; the other side can ask for it, and
+            // spectators just wait for it.
+            match (game.spectating(), game.guest()) {
+                (true, _) => row![],
+                (false, true) => row![action("rematch", GameOverItem::Rematch)],
+                (false, false) => row![
+                    action("rematch", GameOverItem::Rematch),
+                    action("swap_colors", GameOverItem::SwapColors)
+                ],
+            }
+            .spacing(10),
+            row![
+                action("review_game", GameOverItem::Review),
+                action("back_to_menu", GameOverItem::BackToMenu)
+            ]
+            .spacing(10),
+        ]
+        .spacing(15)
+        .align_items(Alignment::Center),
+    )
+    .center_x()
+    .center_y()
+}
+
+/// Search depth for reviewing a finished game, one search per move.
+const REVIEW_DEPTH: usize = 4;
+/// Discs a move has to give away, compared with the engine's choice, to count as a mistake.
+const MISTAKE_THRESHOLD: i32 = 6;
+
+/// Evaluation graph and jump-to-mistake buttons shown while reviewing a finished game.
+fn review_panel(game: &Game) -> Column<'_, Message> {
+    let catalog = &game.menu.catalog;
+    let Some(annotations) = &game.review else {
+        return column![text(catalog.get("reviewing"))];
+    };
+    let shown = match &game.viewing {
+        Some((plies, _)) => *plies,
+        None => game.history.len(),
+    };
+    let mistakes = annotations
+        .iter()
+        .filter(|annotation| annotation.mistake)
+        .fold(
+            row![text(catalog.get("mistakes"))]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            |mistakes, annotation| {
+                mistakes.push(
+                    button(text(catalog.fill(
+                        "mistake",
+                        &[
+                            ("color", &catalog.color(annotation.color)),
+                            ("played", &square_name(annotation.played.square)),
+                            ("loss", &annotation.loss),
+                            ("best", &square_name(annotation.best.square)),
+                        ],
+                    )))
+                    .on_press(Message::ShowPly(Some(annotation.ply + 1)))
+                    .style(theme::Button::Destructive),
+                )
+            },
+        );
+    column![
+        canvas(EvaluationGraph::new(annotations, game.history.len(), shown))
+            .width(Length::Fill)
+            .height(Length::Fixed(120.0)),
+        scrollable(mistakes).direction(scrollable::Direction::Horizontal(
+            scrollable::Properties::default()
+        )),
+    ]
+    .spacing(10)
+}
+
+/// How many of the engine's suggestions a hint shows.
+const HINT_MOVES: usize = 3;
+
+fn playfield(game: &Game) -> Container<'_, Message> {
+    let settings = &game.menu.settings;
+    let catalog = &game.menu.catalog;
+    let (white_stones, black_stones) = (game.game_board.white_count, game.game_board.black_count);
+    let pass_notice = match game.pass_notice.filter(|_| game.viewing.is_none()) {
+        Some(color) => catalog.fill(
+            "no_moves",
+            &[
+                ("name", &game.player_name(color)),
+                ("other", &game.player_name(color.reverse())),
+            ],
+        ),
+        None => String::new(),
+    };
+    let mut toolbar = row![button(text(catalog.get("reset")))
+        .on_press(Message::Reset)
+        .style(theme::Button::Destructive)]
+    .spacing(10)
+    .padding(20)
+    .align_items(Alignment::Center);
+    if game.reviewing {
+        toolbar = toolbar.push(
+            button(text(catalog.get("results")))
+                .on_press(Message::GameOverMessage(GameOverItem::Results))
+                .style(theme::Button::Secondary),
+        )
+    }
+    if game.players_turn() && game.mode() != Mode::Analysis {
+        toolbar = toolbar.push(
+            button(text(catalog.get("hint")))
+                .on_press(Message::Hint)
+                .style(theme::Button::Secondary),
+        )
+    }
+    toolbar = toolbar.push(
+        button(text(match game.menu.settings.show_evaluation {
+            true => catalog.get("hide_evaluation"),
+            false => catalog.get("show_evaluation"),
+        }))
+        .on_press(Message::MenuMessage(MenuItem::ToggleEvaluation))
+        .style(theme::Button::Secondary),
+    );
+    let hint = match game.shown_evaluations() {
+        Some(_) if game.mode() == Mode::Analysis => match &game.analysis {
+            Some((_, depth, _)) => catalog.fill("analysis_depth", &[("depth", depth)]),
+            None => String::new(),
+        },
+        Some(evaluations) => evaluations
+            .iter()
+            .map(|evaluation| {
+                format!(
+                    "{} ({:+})",
+                    square_name(evaluation.square),
+                    evaluation.differential
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("   "),
+        None => String::new(),
+    };
+    let display_size = settings
+        .display_size
+        .pixels()
+        .map_or(Length::Fill, Length::Fixed);
+    let mut board_row = row![].spacing(20);
+    if game.menu.settings.show_evaluation {
+        board_row = board_row.push(
+            canvas(EvaluationBar::new(game.shown_evaluation()))
+                .width(Length::Fixed(24.0))
+                .height(Length::Fill),
+        )
+    }
+    let board_row = board_row
+        .push(
+            canvas(Playboard::new(game))
+                .width(display_size)
+                .height(display_size),
+        )
+        .push(move_list(game));
+    // The last move spelled out, for anyone who can't make it out on the board.
+    let status = match settings.accessibility {
+        true => status_line(game.shown_history(), game.shown_board(), catalog),
+        false => String::new(),
+    };
+    let connection = match &game.lan {
+        Some(lan) => lan_status(lan, catalog),
+        None => String::new(),
+    };
+    let mut layout = column![
+        toolbar,
+        text(connection).size(20),
+        text(pass_notice).size(20),
+        text(status).size(20),
+        text(hint),
+        board_row,
+        text(match &game.clocks {
+            Some(clocks) => format!(
+                "{} {}       {} {}",
+                game.player_name(StoneColor::Black),
+                clocks.display(StoneColor::Black, game.now),
+                game.player_name(StoneColor::White),
+                clocks.display(StoneColor::White, game.now)
+            ),
+            None => String::new(),
+        })
+        .size(24),
+        row![text(format!(
+            "{}:{white_stones}       {}:{black_stones}",
+            game.player_name(StoneColor::White),
+            game.player_name(StoneColor::Black)
+        ))]
+        .padding(20)
+        .align_items(Alignment::Center),
+        row![text(match game.game_board.win {
+            GameOutcome::Win(color) => catalog.fill("wins", &[("name", &game.player_name(color))]),
+            GameOutcome::Draw => catalog.get("draw").to_owned(),
+            GameOutcome::InProgress => catalog.get("awaiting_results").to_owned(),
+        })]
+    ]
+    .padding(20)
+    .align_items(Alignment::Center);
+    if game.reviewing {
+        layout = layout.push(review_panel(game))
+    }
+    container(layout)
+}
+
+/// Where the LAN connection stands, for the line above the board.
+fn lan_status(lan: &net::Session, catalog: &Catalog) -> String {
+    match (&lan.status, lan.role()) {
+        (net::Status::Waiting, net::Role::Host(port)) => {
+            catalog.fill("lan_hosting", &[("port", port)])
+        }
+        (net::Status::Waiting, net::Role::Join(address) | net::Role::Watch(address)) => {
+            catalog.fill("lan_joining", &[("address", address)])
+        }
+        (net::Status::Connected, net::Role::Watch(_)) => catalog.get("lan_watching").to_owned(),
+        (net::Status::Connected, _) => {
+            catalog.fill("lan_connected", &[("color", &catalog.color(lan.color))])
+        }
+        (net::Status::Resyncing, _) => catalog.get("lan_resyncing").to_owned(),
+        (net::Status::Lost(reason), _) => catalog.fill("lan_lost", &[("reason", reason)]),
+        (net::Status::Refused(reason), _) => catalog.fill("lan_refused", &[("reason", reason)]),
+    }
+}
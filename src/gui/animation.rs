@@ -0,0 +1,105 @@
+use crate::{index_to_pair, AnimationSpeed, Ply, StoneColor, Tile};
+use iced::Color;
+use std::time::{Duration, Instant};
+
+/// One stone appearing or turning over, `delay` after the animation started.
+struct Event {
+    square: usize,
+    color: StoneColor,
+    delay: Duration,
+    placed: bool,
+}
+
+/// Plays back the moves made in one update: each new stone grows in, then the
+/// stones it turns over fade to the new color, nearest ones first.
+pub struct Animation {
+    start: Instant,
+    before: Vec<Tile>,
+    events: Vec<Event>,
+    duration: Duration,
+    length: Duration,
+}
+impl Animation {
+    pub fn new(before: Vec<Tile>, plies: &[Ply], speed: AnimationSpeed, start: Instant) -> Self {
+        let (duration, step) = (speed.duration(), speed.step());
+        let mut events = Vec::new();
+        let mut offset = Duration::ZERO;
+        for ply in plies {
+            let Some(square) = ply.square else {
+                continue;
+            };
+            let (row, column) = index_to_pair(square);
+            events.push(Event {
+                square,
+                color: ply.color,
+                delay: offset,
+                placed: true,
+            });
+            let mut furthest = 0;
+            for &id in &ply.flipped {
+                let (i, j) = index_to_pair(id);
+                let distance = row.abs_diff(i).max(column.abs_diff(j));
+                furthest = furthest.max(distance);
+                events.push(Event {
+                    square: id,
+                    color: ply.color,
+                    delay: offset + step * distance as u32,
+                    placed: false,
+                });
+            }
+            offset += step * furthest as u32 + duration;
+        }
+        Animation {
+            start,
+            before,
+            events,
+            duration,
+            length: offset,
+        }
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.duration_since(self.start) >= self.length
+    }
+
+    /// The color and size (as a fraction of the full radius) to draw `square` with at
+    /// `now`, or `None` if the square isn't animated and should be drawn as it is on the board.
+    /// `disc` gives the color of each side's discs.
+    pub fn tile(
+        &self,
+        square: usize,
+        now: Instant,
+        disc: impl Fn(StoneColor) -> Color,
+    ) -> Option<(Option<Color>, f32)> {
+        let elapsed = now.duration_since(self.start);
+        let mut events = self.events.iter().filter(|event| event.square == square);
+        let first = events.next()?;
+        if elapsed < first.delay {
+            return Some((self.before[square].0.map(disc), 1.0));
+        }
+        let mut previous = self.before[square].0;
+        let mut current = first;
+        for event in events {
+            if elapsed < event.delay {
+                break;
+            }
+            previous = Some(current.color);
+            current = event;
+        }
+        let progress =
+            ((elapsed - current.delay).as_secs_f32() / self.duration.as_secs_f32()).min(1.0);
+        let color = disc(current.color);
+        Some(match (current.placed, previous) {
+            (true, _) | (false, None) => (Some(color), progress),
+            (false, Some(previous)) => (Some(mix(disc(previous), color, progress)), 1.0),
+        })
+    }
+}
+
+fn mix(from: Color, to: Color, progress: f32) -> Color {
+    Color::from_rgb(
+        from.r + (to.r - from.r) * progress,
+        from.g + (to.g - from.g) * progress,
+        from.b + (to.b - from.b) * progress,
+    )
+}
@@ -15,11 +15,22 @@ use iced::{Border, Color, Element, Length, Rectangle, Size};
 pub struct Circle {
     radius: f32,
     color: Color,
+    outline: Option<Color>,
 }
 
 impl Circle {
     pub fn new(radius: f32, color: Color) -> Self {
-        Self { radius, color }
+        Self {
+            radius,
+            color,
+            outline: None,
+        }
+    }
+
+    /// Rings the circle in `color`, so it stands out whatever it is drawn on.
+    pub fn outline(mut self, color: Option<Color>) -> Self {
+        self.outline = color;
+        self
     }
 }
 
@@ -60,7 +71,14 @@ where
         renderer.fill_quad(
             renderer::Quad {
                 bounds: layout.bounds(),
-                border: Border::with_radius(self.radius),
+                border: match self.outline {
+                    Some(color) => Border {
+                        color,
+                        width: self.radius / 8.0,
+                        radius: self.radius.into(),
+                    },
+                    None => Border::with_radius(self.radius),
+                },
                 ..renderer::Quad::default()
             },
             self.color,
@@ -0,0 +1,190 @@
+use crate::{review::Annotation, Message};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    mouse,
+    widget::canvas::{self, event, Event, Frame, Geometry, Path, Stroke, Text},
+    Color, Point, Rectangle, Renderer, Size, Theme,
+};
+
+const BACKGROUND: Color = Color::from_rgb(0.93, 0.93, 0.93);
+const AXIS: Color = Color::from_rgb(0.6, 0.6, 0.6);
+const LINE: Color = Color::from_rgb(0.1, 0.1, 0.1);
+const MISTAKE: Color = Color::from_rgb(0.9, 0.2, 0.2);
+const SHOWN: Color = Color::from_rgb(0.2, 0.4, 0.9);
+const BAR_BLACK: Color = Color::from_rgb(0.1, 0.1, 0.1);
+const BAR_WHITE: Color = Color::from_rgb(0.95, 0.95, 0.95);
+
+/// The engine's evaluation after every move of a reviewed game, Black up and White
+/// down. Clicking a point shows the position after that move.
+pub struct EvaluationGraph<'a> {
+    annotations: &'a [Annotation],
+    plies: usize,
+    shown: usize,
+}
+impl<'a> EvaluationGraph<'a> {
+    pub fn new(annotations: &'a [Annotation], plies: usize, shown: usize) -> Self {
+        EvaluationGraph {
+            annotations,
+            plies,
+            shown,
+        }
+    }
+
+    fn x(&self, plies: usize, width: f32) -> f32 {
+        plies as f32 / self.plies.max(1) as f32 * width
+    }
+}
+
+impl canvas::Program<Message> for EvaluationGraph<'_> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match (event, cursor.position_in(bounds)) {
+            (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some(point)) => {
+                let nearest = self.annotations.iter().min_by(|a, b| {
+                    let distance = |annotation: &Annotation| {
+                        (self.x(annotation.ply + 1, bounds.width) - point.x).abs()
+                    };
+                    distance(a).total_cmp(&distance(b))
+                });
+                match nearest {
+                    Some(annotation) => (
+                        event::Status::Captured,
+                        Some(Message::ShowPly(Some(annotation.ply + 1))),
+                    ),
+                    None => (event::Status::Ignored, None),
+                }
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let (width, height) = (bounds.width, bounds.height);
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), BACKGROUND);
+
+        let middle = height / 2.0;
+        let range = self
+            .annotations
+            .iter()
+            .map(|annotation| annotation.black_evaluation().abs())
+            .max()
+            .unwrap_or(0)
+            .max(8) as f32;
+        let point = |annotation: &Annotation| {
+            Point::new(
+                self.x(annotation.ply + 1, width),
+                middle - annotation.black_evaluation() as f32 / range * (middle - 4.0),
+            )
+        };
+        frame.stroke(
+            &Path::line(Point::new(0.0, middle), Point::new(width, middle)),
+            Stroke::default().with_color(AXIS),
+        );
+        let shown = self.x(self.shown, width);
+        frame.fill_rectangle(Point::new(shown - 1.0, 0.0), Size::new(2.0, height), SHOWN);
+        let line = Path::new(|builder| {
+            builder.move_to(Point::new(0.0, middle));
+            for annotation in self.annotations {
+                builder.line_to(point(annotation));
+            }
+        });
+        frame.stroke(&line, Stroke::default().with_color(LINE).with_width(2.0));
+        for annotation in self.annotations.iter().filter(|a| a.mistake) {
+            frame.fill(&Path::circle(point(annotation), 4.0), MISTAKE);
+        }
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &(),
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match cursor.is_over(bounds) {
+            true => mouse::Interaction::Pointer,
+            false => mouse::Interaction::default(),
+        }
+    }
+}
+
+/// Who is ahead, as a bar split between Black at the bottom and White at the top.
+/// `None` while the engine is still thinking.
+pub struct EvaluationBar {
+    black_evaluation: Option<i32>,
+}
+impl EvaluationBar {
+    pub fn new(black_evaluation: Option<i32>) -> Self {
+        EvaluationBar { black_evaluation }
+    }
+}
+
+impl canvas::Program<Message> for EvaluationBar {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), BAR_WHITE);
+        let evaluation = self.black_evaluation.unwrap_or(0);
+        let black_share = 0.5 + evaluation.clamp(-32, 32) as f32 / 64.0;
+        let black_height = bounds.height * black_share;
+        frame.fill_rectangle(
+            Point::new(0.0, bounds.height - black_height),
+            Size::new(bounds.width, black_height),
+            BAR_BLACK,
+        );
+        frame.stroke(
+            &Path::line(
+                Point::new(0.0, bounds.height / 2.0),
+                Point::new(bounds.width, bounds.height / 2.0),
+            ),
+            Stroke::default().with_color(AXIS),
+        );
+        if let Some(evaluation) = self.black_evaluation {
+            let (position, color, vertical_alignment) = match evaluation >= 0 {
+                true => (
+                    Point::new(bounds.width / 2.0, bounds.height - 4.0),
+                    BAR_WHITE,
+                    Vertical::Bottom,
+                ),
+                false => (
+                    Point::new(bounds.width / 2.0, 4.0),
+                    BAR_BLACK,
+                    Vertical::Top,
+                ),
+            };
+            frame.fill_text(Text {
+                content: format!("{:+}", evaluation),
+                position,
+                color,
+                size: 12.0.into(),
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment,
+                ..Text::default()
+            })
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,349 @@
+use super::Game;
+use crate::{
+    index_to_pair, pair_to_index, GameOutcome, Message, PlayerOrComputer, StoneColor, HEIGHT, WIDTH,
+};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    mouse,
+    widget::canvas::{self, event, Event, Frame, Geometry, Path, Stroke, Text},
+    Color, Point, Rectangle, Renderer, Size, Theme,
+};
+
+/// Shifts a stone's color towards `tint` so freshly turned stones stand out.
+fn tinted(color: Color, tint: Color) -> Color {
+    Color::from_rgb(
+        color.r * 0.7 + 0.3 * tint.r,
+        color.g * 0.7 + 0.3 * tint.g,
+        color.b * 0.7 + 0.3 * tint.b,
+    )
+}
+
+/// Where the squares sit inside the canvas. The board is kept square and centered,
+/// with half a square of felt around it for the coordinates.
+pub struct Grid {
+    origin: Point,
+    cell: f32,
+}
+impl Grid {
+    pub fn new(size: Size) -> Self {
+        let cell = (size.width / (WIDTH as f32 + 1.0)).min(size.height / (HEIGHT as f32 + 1.0));
+        Grid {
+            origin: Point::new(
+                (size.width - cell * WIDTH as f32) / 2.0,
+                (size.height - cell * HEIGHT as f32) / 2.0,
+            ),
+            cell,
+        }
+    }
+
+    pub fn center(&self, id: usize) -> Point {
+        let (row, column) = index_to_pair(id);
+        Point::new(
+            self.origin.x + (column as f32 + 0.5) * self.cell,
+            self.origin.y + (row as f32 + 0.5) * self.cell,
+        )
+    }
+
+    /// The square under `point`, or `None` on the felt around the board. Each square
+    /// takes in its top and left edges but not its bottom and right ones.
+    pub fn square_at(&self, point: Point) -> Option<usize> {
+        let column = (point.x - self.origin.x) / self.cell;
+        let row = (point.y - self.origin.y) / self.cell;
+        // A canvas with no room for the board gives NaN, which no range contains.
+        if !(0.0..WIDTH as f32).contains(&column) || !(0.0..HEIGHT as f32).contains(&row) {
+            return None;
+        }
+        Some(pair_to_index(row as usize, column as usize))
+    }
+}
+
+/// The game board as a canvas: felt, grid lines, star points, a–h/1–8 labels and
+/// the stones, scaled to whatever space the layout gives it.
+pub struct Playboard<'a> {
+    game: &'a Game,
+}
+impl<'a> Playboard<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Playboard { game }
+    }
+}
+
+impl canvas::Program<Message> for Playboard<'_> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+            if self.game.viewing.is_some() || self.game.spectating() {
+                return (event::Status::Ignored, None);
+            }
+            if let Some(id) = cursor
+                .position_in(bounds)
+                .and_then(|point| Grid::new(bounds.size()).square_at(point))
+            {
+                let (row, column) = index_to_pair(id);
+                return (
+                    event::Status::Captured,
+                    Some(Message::EmptyPressed(row, column)),
+                );
+            }
+        }
+        (event::Status::Ignored, None)
+    }
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let game = self.game;
+        let board = game.shown_board();
+        let live = game.viewing.is_none();
+        let theme = game.game_theme();
+        let settings = &game.menu.settings;
+        let grid = Grid::new(bounds.size());
+        let cell = grid.cell;
+        let mut frame = Frame::new(renderer, bounds.size());
+        // Accessibility mode rings every disc in the felt's opposite: light or dark.
+        let outline_color = match theme.felt.r + theme.felt.g + theme.felt.b > 1.5 {
+            true => Color::BLACK,
+            false => Color::WHITE,
+        };
+        let outline = |frame: &mut Frame, center: Point, radius: f32| {
+            if settings.accessibility {
+                frame.stroke(
+                    &Path::circle(center, radius),
+                    Stroke::default()
+                        .with_color(outline_color)
+                        .with_width((cell / 30.0).max(1.5)),
+                )
+            }
+        };
+        // A cross on black discs and a ring on white ones, in the other side's color.
+        let symbol = |frame: &mut Frame, center: Point, radius: f32, color: StoneColor| {
+            let stroke = Stroke::default()
+                .with_color(theme.disc(color.reverse()))
+                .with_width((cell / 24.0).max(1.5));
+            match color {
+                StoneColor::Black => {
+                    let arm = radius * 0.4;
+                    frame.stroke(
+                        &Path::line(
+                            Point::new(center.x - arm, center.y - arm),
+                            Point::new(center.x + arm, center.y + arm),
+                        ),
+                        stroke.clone(),
+                    );
+                    frame.stroke(
+                        &Path::line(
+                            Point::new(center.x - arm, center.y + arm),
+                            Point::new(center.x + arm, center.y - arm),
+                        ),
+                        stroke,
+                    )
+                }
+                StoneColor::White => frame.stroke(&Path::circle(center, radius * 0.45), stroke),
+            }
+        };
+
+        frame.fill_rectangle(
+            Point::new(grid.origin.x - cell / 2.0, grid.origin.y - cell / 2.0),
+            Size::new(cell * (WIDTH as f32 + 1.0), cell * (HEIGHT as f32 + 1.0)),
+            theme.felt,
+        );
+        let lines = Stroke::default()
+            .with_color(theme.lines)
+            .with_width((cell / 40.0).max(1.0));
+        for row in 0..=HEIGHT {
+            let y = grid.origin.y + row as f32 * cell;
+            let line = Path::line(
+                Point::new(grid.origin.x, y),
+                Point::new(grid.origin.x + WIDTH as f32 * cell, y),
+            );
+            frame.stroke(&line, lines.clone());
+        }
+        for column in 0..=WIDTH {
+            let x = grid.origin.x + column as f32 * cell;
+            let line = Path::line(
+                Point::new(x, grid.origin.y),
+                Point::new(x, grid.origin.y + HEIGHT as f32 * cell),
+            );
+            frame.stroke(&line, lines.clone());
+        }
+        for row in [2, HEIGHT - 2] {
+            for column in [2, WIDTH - 2] {
+                let point = Point::new(
+                    grid.origin.x + column as f32 * cell,
+                    grid.origin.y + row as f32 * cell,
+                );
+                frame.fill(&Path::circle(point, cell / 14.0), theme.lines);
+            }
+        }
+        let label = |content: String, position: Point| Text {
+            content,
+            position,
+            color: theme.labels,
+            size: (cell * 0.3).into(),
+            horizontal_alignment: Horizontal::Center,
+            vertical_alignment: Vertical::Center,
+            ..Text::default()
+        };
+        for column in (0..WIDTH).filter(|_| settings.show_coordinates) {
+            let x = grid.origin.x + (column as f32 + 0.5) * cell;
+            let letter = ((b'a' + column as u8) as char).to_string();
+            frame.fill_text(label(
+                letter.clone(),
+                Point::new(x, grid.origin.y - cell / 4.0),
+            ));
+            frame.fill_text(label(
+                letter,
+                Point::new(x, grid.origin.y + (HEIGHT as f32 + 0.25) * cell),
+            ));
+        }
+        for row in (0..HEIGHT).filter(|_| settings.show_coordinates) {
+            let y = grid.origin.y + (row as f32 + 0.5) * cell;
+            frame.fill_text(label(
+                (row + 1).to_string(),
+                Point::new(grid.origin.x - cell / 4.0, y),
+            ));
+            frame.fill_text(label(
+                (row + 1).to_string(),
+                Point::new(grid.origin.x + (WIDTH as f32 + 0.25) * cell, y),
+            ));
+        }
+
+        let radius = cell * 0.42;
+        let hovered = cursor
+            .position_in(bounds)
+            .and_then(|point| grid.square_at(point));
+        let legal_moves = match live
+            && board.win == GameOutcome::InProgress
+            && game.menu.settings.show_legal_moves
+            && game.movers().0 == PlayerOrComputer::Player
+        {
+            true => board.legal_moves(board.turn),
+            false => Vec::new(),
+        };
+        let turn_color = theme.disc(board.turn);
+        let ghost = Color {
+            a: match board.turn {
+                StoneColor::Black => 0.4,
+                StoneColor::White => 0.6,
+            },
+            ..turn_color
+        };
+        let last_move = game
+            .shown_history()
+            .iter()
+            .rfind(|ply| !ply.is_pass())
+            .filter(|_| game.menu.settings.show_last_move);
+        for id in 0..WIDTH * HEIGHT {
+            let center = grid.center(id);
+            let animated = game
+                .animation
+                .as_ref()
+                .filter(|_| live)
+                .and_then(|animation| animation.tile(id, game.now, |color| theme.disc(color)));
+            let stone = board.board[id].0.map(|color| theme.disc(color));
+            match (animated, stone) {
+                (Some((Some(color), scale)), _) => {
+                    frame.fill(&Path::circle(center, radius * scale), color);
+                    outline(&mut frame, center, radius * scale);
+                }
+                (Some((None, _)), _) => {}
+                (None, Some(color)) => {
+                    let color = match last_move {
+                        Some(ply) if ply.flipped.contains(&id) => tinted(color, theme.last_move),
+                        _ => color,
+                    };
+                    frame.fill(&Path::circle(center, radius), color);
+                    outline(&mut frame, center, radius);
+                    if let Some(color) = board.board[id].0.filter(|_| settings.disc_symbols) {
+                        symbol(&mut frame, center, radius, color);
+                    }
+                    if last_move.is_some_and(|ply| ply.square == Some(id)) {
+                        frame.stroke(
+                            &Path::circle(center, radius),
+                            Stroke::default()
+                                .with_color(theme.last_move)
+                                .with_width((cell / 20.0).max(2.0)),
+                        )
+                    }
+                }
+                (None, None) if legal_moves.contains(&id) => {
+                    match game.menu.settings.show_flip_counts && hovered == Some(id) {
+                        true => frame.fill_text(Text {
+                            content: board.flips(id, board.turn).to_string(),
+                            position: center,
+                            color: turn_color,
+                            size: (cell * 0.45).into(),
+                            horizontal_alignment: Horizontal::Center,
+                            vertical_alignment: Vertical::Center,
+                            ..Text::default()
+                        }),
+                        false => frame.fill(&Path::circle(center, cell / 8.0), ghost),
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        if let Some(evaluations) = game.shown_evaluations() {
+            for (n, evaluation) in evaluations.iter().enumerate() {
+                let center = grid.center(evaluation.square);
+                if n == 0 {
+                    frame.stroke(
+                        &Path::circle(center, radius),
+                        Stroke::default()
+                            .with_color(theme.hint)
+                            .with_width((cell / 16.0).max(2.0)),
+                    )
+                }
+                frame.fill_text(Text {
+                    content: format!("{:+}", evaluation.differential),
+                    position: center,
+                    color: theme.hint,
+                    size: (cell * 0.3).into(),
+                    horizontal_alignment: Horizontal::Center,
+                    vertical_alignment: Vertical::Center,
+                    ..Text::default()
+                })
+            }
+        }
+        if let Some(id) = game.cursor.filter(|_| live) {
+            let center = grid.center(id);
+            frame.stroke(
+                &Path::rectangle(
+                    Point::new(center.x - cell / 2.0, center.y - cell / 2.0),
+                    Size::new(cell, cell),
+                ),
+                Stroke::default()
+                    .with_color(theme.cursor)
+                    .with_width((cell / 16.0).max(2.0)),
+            )
+        }
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &(),
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match cursor
+            .position_in(bounds)
+            .and_then(|point| Grid::new(bounds.size()).square_at(point))
+        {
+            Some(_) if self.game.viewing.is_none() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
@@ -1,17 +1,40 @@
-use circles::circle;
-use iced::{
-    alignment::{Horizontal, Vertical},
-    widget::{button, column, container, row, text, Column, Container, Row},
-    {theme, Color, Length}, {Alignment, Element, Sandbox},
-};
 use std::{
-    cmp::Ordering,
+    cmp::{Ordering, Reverse},
     fmt::{Display, Formatter, Result},
     sync::mpsc,
     thread,
+    time::{Duration, Instant},
 };
 use PlayerOrComputer::*;
-mod circles;
+pub mod cli;
+pub mod clock;
+#[cfg(feature = "gui")]
+mod gui;
+pub mod locale;
+pub mod net;
+mod notation;
+pub mod paths;
+pub mod review;
+pub mod save;
+pub mod server;
+pub mod settings;
+// The first tests import more than they use and cast indices that are already `usize`.
+#[cfg(test)]
+#[allow(unused_imports, clippy::unnecessary_cast)]
+mod test;
+#[cfg(feature = "gui")]
+pub mod themes;
+pub mod tui;
+pub use clock::TimeControl;
+#[cfg(feature = "gui")]
+pub use gui::{Game, Menu};
+pub use locale::{Catalog, Language};
+pub use notation::{parse_square, square_name, status_line};
+pub use paths::Paths;
+pub use review::{review_game, review_game_from, Annotation};
+pub use settings::{AnimationSpeed, DisplaySize};
+#[cfg(feature = "gui")]
+pub use themes::GameTheme;
 
 pub const WIDTH: usize = 8;
 pub const HEIGHT: usize = 8;
@@ -28,6 +51,8 @@ pub const fn index_to_pair(id: usize) -> (usize, usize) {
 #[derive(Clone)]
 struct Node {
     pub value: usize,
+    /// Disc count difference for the original player at the end of the chosen line.
+    pub differential: i32,
     pub is_original_player: bool,
     pub board: Board,
     pub children: Vec<Node>,
@@ -51,6 +76,7 @@ impl Node {
                     .unwrap();
 
                 self.value = chosen_child.value;
+                self.differential = chosen_child.differential;
                 if chosen_child.option_id.is_some() {
                     self.option_id = chosen_child.option_id
                 };
@@ -64,6 +90,7 @@ impl Node {
                     .unwrap();
 
                 self.value = chosen_child.value;
+                self.differential = chosen_child.differential;
                 if chosen_child.option_id.is_some() {
                     self.option_id = chosen_child.option_id
                 };
@@ -79,12 +106,106 @@ impl Node {
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerOrComputer {
     Player,
     Computer,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    VsComputer,
+    TwoPlayers,
+    /// Both colors are played by hand while the engine scores every legal move.
+    Analysis,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    #[default]
+    Hard,
+}
+impl Difficulty {
+    /// How many plies `minmax_helper` looks ahead past the first move.
+    pub const fn depth(&self) -> usize {
+        match self {
+            Self::Easy => 2,
+            Self::Medium => 4,
+            Self::Hard => 6,
+        }
+    }
+}
+
+/// How far the computer looks ahead: as deep as `difficulty` allows, cut short to
+/// fit in `budget` when it is playing on the clock.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Search {
+    pub difficulty: Difficulty,
+    pub budget: Option<Duration>,
+}
+impl From<Difficulty> for Search {
+    fn from(difficulty: Difficulty) -> Self {
+        Search {
+            difficulty,
+            budget: None,
+        }
+    }
+}
+
+/// A single turn of a game, in the order it was played.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ply {
+    pub color: StoneColor,
+    /// `None` when `color` had no legal move and had to pass.
+    pub square: Option<usize>,
+    /// The stones this move turned over.
+    pub flipped: Vec<usize>,
+}
+impl Ply {
+    pub fn pass(color: StoneColor) -> Self {
+        Ply {
+            color,
+            square: None,
+            flipped: Vec::new(),
+        }
+    }
+
+    pub fn is_pass(&self) -> bool {
+        self.square.is_none()
+    }
+}
+
+/// Appends a move to `history`, first recording a pass for the opponent if the
+/// same color also made the previous move.
+fn record(history: &mut Vec<Ply>, ply: Ply) {
+    if history
+        .last()
+        .is_some_and(|last| last.color == ply.color && !last.is_pass())
+    {
+        history.push(Ply::pass(ply.color.reverse()))
+    }
+    history.push(ply)
+}
+
+/// What the search thinks of one move.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Evaluation {
+    pub square: usize,
+    /// The search's own score, only meaningful next to other moves from the same position.
+    pub score: usize,
+    /// How many discs the mover expects to be ahead by at the end of the line the search settled on.
+    pub differential: i32,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StoneColor {
     White,
     Black,
@@ -99,9 +220,11 @@ impl StoneColor {
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile(pub Option<StoneColor>);
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameOutcome {
     Win(StoneColor),
     Draw,
@@ -230,6 +353,34 @@ impl Board {
         false
     }
 
+    /// Every square `color` could play on right now.
+    pub fn legal_moves(&self, color: StoneColor) -> Vec<usize> {
+        let mut neighbours = Vec::with_capacity(8);
+        (0..WIDTH * HEIGHT)
+            .filter(|&id| {
+                let (row, column) = index_to_pair(id);
+                self.next_to_taken[id]
+                    && self.clone().make_move(row, column, color, &mut neighbours)
+            })
+            .collect()
+    }
+
+    /// How many of the opponent's stones playing on `id` would turn over, 0 if the move is illegal.
+    pub fn flips(&self, id: usize, color: StoneColor) -> usize {
+        let (row, column) = index_to_pair(id);
+        let mut board = self.clone();
+        match board.make_move(row, column, color, &mut Vec::with_capacity(8)) {
+            true => board.count_of(color) - self.count_of(color) - 1,
+            false => 0,
+        }
+    }
+
+    /// Whether `color` has no legal move while the opponent still has one,
+    /// so the turn goes straight back to the opponent.
+    pub fn must_pass(&self, color: StoneColor) -> bool {
+        !self.moves_are_possible(color) && self.moves_are_possible(color.reverse())
+    }
+
     pub fn make_move(
         &mut self,
         row: usize,
@@ -300,6 +451,44 @@ impl Board {
         }
         move_was_made
     }
+    /// [`Board::make_move`] on a square id, returning what happened if the move was legal.
+    pub fn play_move(&mut self, id: usize, color: StoneColor) -> Option<Ply> {
+        let before = self.board.clone();
+        let (row, column) = index_to_pair(id);
+        if !self.make_move(row, column, color, &mut Vec::with_capacity(8)) {
+            return None;
+        }
+        let flipped = (0..WIDTH * HEIGHT)
+            .filter(|&other| other != id && before[other] != self.board[other])
+            .collect();
+        Some(Ply {
+            color,
+            square: Some(id),
+            flipped,
+        })
+    }
+    /// The position reached by playing `history` from the starting position,
+    /// with the turn passed on the same way [`Board::colored_move`] does.
+    pub fn from_history(history: &[Ply]) -> Board {
+        Board::new().replay(history)
+    }
+    /// Like [`Board::from_history`], but starting from this position.
+    pub fn replay(&self, history: &[Ply]) -> Board {
+        let mut board = self.clone();
+        for ply in history {
+            if let Some(square) = ply.square {
+                board.play_move(square, ply.color);
+            }
+        }
+        if let Some(last) = history.last() {
+            board.turn = match board.moves_are_possible(last.color.reverse()) {
+                true => last.color.reverse(),
+                false => last.color,
+            };
+        }
+        board.win = board.wincheck();
+        board
+    }
     fn count_of(&self, color: StoneColor) -> usize {
         let (white_tiles, black_tiles) = (self.white_count, self.black_count);
         match color {
@@ -307,6 +496,10 @@ impl Board {
             StoneColor::Black => black_tiles,
         }
     }
+    /// `color`'s stones minus the opponent's.
+    pub fn differential(&self, color: StoneColor) -> i32 {
+        self.count_of(color) as i32 - self.count_of(color.reverse()) as i32
+    }
     fn wincheck(&self) -> GameOutcome {
         let (white_tiles, black_tiles) = (self.white_count, self.black_count);
         if self.board.iter().all(|&x| x.0.is_some())
@@ -323,18 +516,56 @@ impl Board {
         }
     }
     pub fn minmax_move(&mut self, color: StoneColor) -> bool {
+        match self.best_move(color, Difficulty::default().depth()) {
+            Some(id) => {
+                let (row, column) = index_to_pair(id);
+                self.make_move(row, column, color, &mut Vec::with_capacity(8))
+            }
+            None => false,
+        }
+    }
+    /// The square `minmax_move` would play for `color`, without playing it.
+    pub fn best_move(&self, color: StoneColor, depth: usize) -> Option<usize> {
+        self.evaluate_moves(color, depth)
+            .first()
+            .map(|evaluation| evaluation.square)
+    }
+    /// Deepens the search one ply at a time, up to `max_depth`, and stops before a
+    /// deeper search would likely run past `budget`.
+    pub fn best_move_within(
+        &self,
+        color: StoneColor,
+        max_depth: usize,
+        budget: Duration,
+    ) -> Option<usize> {
+        let start = Instant::now();
+        let mut best = None;
+        for depth in 2..=max_depth.max(2) {
+            let before = Instant::now();
+            best = self.best_move(color, depth).or(best);
+            // Each extra ply costs several times the previous one.
+            if start.elapsed() + before.elapsed() * 4 > budget {
+                break;
+            }
+        }
+        best
+    }
+    /// Runs the search `minmax_move` uses and reports every legal move for `color`,
+    /// best first, without playing any of them. Depths below 2 search as deep as 2,
+    /// the shallowest search there is.
+    pub fn evaluate_moves(&self, color: StoneColor, depth: usize) -> Vec<Evaluation> {
         let player = color;
         let opponent = player.reverse();
         let board = self.clone();
-        let mut node_with_score = Node {
+        let node_with_score = Node {
             is_original_player: false,
             value: 0,
+            differential: 0,
             board,
             children: Vec::with_capacity(32),
             option_id: None,
         };
         let mut second_node_with_score = node_with_score.clone();
-        let mut ids = Vec::with_capacity(32);
         let mut neighbours = Vec::with_capacity(8);
         for id in self.next_to_taken.iter().enumerate().fold(
             Vec::with_capacity(64),
@@ -350,10 +581,10 @@ impl Board {
             let (row, column) = index_to_pair(id);
             let mut current_board = second_node_with_score.board.clone();
             if current_board.make_move(row, column, player, &mut neighbours) {
-                ids.push(id);
                 second_node_with_score.add_child(Node {
                     is_original_player: true,
                     value: 0,
+                    differential: 0,
                     board: current_board,
                     children: Vec::with_capacity(10),
                     option_id: Some(id),
@@ -367,23 +598,34 @@ impl Board {
             thread::spawn(move || {
                 let mut neighbours = Vec::with_capacity(8);
                 let mut new_node = child;
-                Self::minmax_helper(opponent, &mut new_node, 6, false, color, &mut neighbours);
+                Self::minmax_helper(
+                    opponent,
+                    &mut new_node,
+                    depth,
+                    false,
+                    color,
+                    &mut neighbours,
+                );
                 txn.send(new_node).unwrap()
             });
         }
         drop(tx);
-        for node in rx {
-            node_with_score.add_child(node);
-        }
-
-        node_with_score.minmax();
-        match node_with_score.option_id {
-            Some(id) => {
-                let (row, column) = index_to_pair(id);
-                return self.make_move(row, column, color, &mut neighbours);
-            }
-            None => false,
-        }
+        let mut evaluations: Vec<Evaluation> = rx
+            .into_iter()
+            .map(|mut node| Evaluation {
+                score: node.minmax(),
+                differential: node.differential,
+                square: node.option_id.unwrap(),
+            })
+            .collect();
+        evaluations.sort_by_key(|evaluation| {
+            (
+                Reverse(evaluation.score),
+                Reverse(evaluation.differential),
+                evaluation.square,
+            )
+        });
+        evaluations
     }
     fn minmax_helper(
         color: StoneColor,
@@ -406,7 +648,7 @@ impl Board {
         );
         let player = color;
         let opponent = player.reverse();
-        if depth == 2 {
+        if depth <= 2 {
             for id in next_to_taken_ids {
                 let (row, column) = index_to_pair(id);
                 let mut current_board = node.board.clone();
@@ -424,6 +666,7 @@ impl Board {
                     node.add_child(Node {
                         is_original_player: false,
                         value: 2 * current_board.count_of(orignal_color) + 1 + corner_boost,
+                        differential: current_board.differential(orignal_color),
                         board: current_board,
                         children: vec![],
                         option_id: None,
@@ -448,6 +691,7 @@ impl Board {
                     node.add_child(Node {
                         is_original_player,
                         value: 0,
+                        differential: 0,
                         board: current_board,
                         children: Vec::with_capacity(10),
                         option_id: None,
@@ -467,6 +711,7 @@ impl Board {
                 }
             });
             node.value = 2 * node.board.count_of(orignal_color) + 1 + corner_boost;
+            node.differential = node.board.differential(orignal_color);
             return;
         }
         // for i in node.children.iter() {
@@ -491,25 +736,62 @@ impl Board {
         mover_other: PlayerOrComputer,
         color: StoneColor,
     ) {
+        self.colored_move_recorded(
+            message,
+            mover_self,
+            mover_other,
+            color,
+            Difficulty::default(),
+            &mut Vec::new(),
+        )
+    }
+    /// Same as [`Board::colored_move`], but searches as `search` says and appends
+    /// every stone placed (the computer's replies included) to `history`.
+    pub fn colored_move_recorded(
+        &mut self,
+        message: Message,
+        mover_self: PlayerOrComputer,
+        mover_other: PlayerOrComputer,
+        color: StoneColor,
+        search: impl Into<Search>,
+        history: &mut Vec<Ply>,
+    ) {
+        let search = search.into();
         let (mover1, mover2) = match (mover_self, mover_other) {
             (Player, Player) => (&message, &message),
             (Player, Computer) => (&message, &Message::ComputerPlays),
             (Computer, Player) => (&Message::ComputerPlays, &message),
             (Computer, Computer) => unreachable!(),
         };
-        let mut neighbours = Vec::with_capacity(8);
-        if match *mover1 {
-            Message::EmptyPressed(i, j) => self.make_move(i, j, color, &mut neighbours),
-            Message::ComputerPlays => self.minmax_move(color),
-            _ => false,
-        } {
+        let square = match *mover1 {
+            Message::EmptyPressed(i, j) => Some(pair_to_index(i, j)),
+            Message::ComputerPlays => match search.budget {
+                Some(budget) => self.best_move_within(color, search.difficulty.depth(), budget),
+                None => self.best_move(color, search.difficulty.depth()),
+            },
+            _ => None,
+        };
+        if let Some(ply) = square.and_then(|id| self.play_move(id, color)) {
+            record(history, ply);
             let wincheck = self.wincheck();
             match wincheck {
                 GameOutcome::InProgress => self.turn = color.reverse(),
                 outcome => self.win = outcome,
             };
             if mover2 == &Message::ComputerPlays {
-                self.colored_move(mover1.clone(), mover_other, mover_self, color.reverse())
+                self.colored_move_recorded(
+                    mover1.clone(),
+                    mover_other,
+                    mover_self,
+                    color.reverse(),
+                    search,
+                    history,
+                )
+            } else if self.win == GameOutcome::InProgress
+                && !self.moves_are_possible(color.reverse())
+            {
+                // Two humans: the opponent has to pass, so the same player goes again.
+                self.turn = color;
             }
         }
         if !self.moves_are_possible(color) {
@@ -523,9 +805,23 @@ impl Board {
                 };
             } else {
                 self.turn = color.reverse();
-                self.colored_move(mover2.clone(), mover_other, mover_self, color.reverse())
+                self.colored_move_recorded(
+                    mover2.clone(),
+                    mover_other,
+                    mover_self,
+                    color.reverse(),
+                    search,
+                    history,
+                )
             }
         }
+        if self.win == GameOutcome::InProgress
+            && history
+                .last()
+                .is_some_and(|last| last.color == self.turn && !last.is_pass())
+        {
+            history.push(Ply::pass(self.turn.reverse()))
+        }
     }
 }
 impl Display for Board {
@@ -594,176 +890,71 @@ impl Default for Board {
     }
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Message {
     EmptyPressed(usize, usize),
     NonEmptyPressed(usize, usize),
     Reset,
     MenuMessage(MenuItem),
     ComputerPlays,
-}
-
-pub struct Game {
-    game_board: Board,
-    menu: Menu,
-}
-
-impl Sandbox for Game {
-    type Message = Message;
-
-    fn new() -> Self {
-        Game {
-            game_board: { Board::new() },
-            menu: Menu::new(),
-        }
-    }
-
-    fn title(&self) -> String {
-        String::from("Reversi - Iced")
-    }
-
-    fn update(&mut self, message: Message) {
-        match message {
-            Message::MenuMessage(menu_item) => match menu_item {
-                MenuItem::ChooseColor(color) => self.menu.chosen_color = color,
-                MenuItem::Play => {
-                    self.menu.play_pressed = true;
-                    if self.game_board.turn != self.menu.chosen_color {
-                        self.game_board.colored_move(
-                            message,
-                            Computer,
-                            Player,
-                            self.game_board.turn,
-                        )
-                    }
-                }
-            },
-            Message::Reset => {
-                *self = Self::new();
-                self.game_board.starting_position();
-            }
-            message => {
-                if self.game_board.turn == self.menu.chosen_color {
-                    self.game_board
-                        .colored_move(message, Player, Computer, self.game_board.turn)
-                } else {
-                    self.game_board
-                        .colored_move(message, Computer, Player, self.game_board.turn)
-                }
-            }
-        }
-    }
-
-    fn view(&self) -> Element<Message> {
-        match self.menu.play_pressed {
-            true => playfield(self),
-            false => menu(self),
-        }
-        .height(Length::Fill)
-        .width(Length::Fill)
-        .center_x()
-        .center_y()
-        .align_x(Horizontal::Center)
-        .align_y(Vertical::Center)
-        .into()
-    }
+    /// Only meaningful inside the running window, so never serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Tick(Instant),
+    ShowPly(Option<usize>),
+    ResumeFromHere,
+    GameOverMessage(GameOverItem),
+    Hint,
+    HintReady(String, Vec<Evaluation>),
+    AnalysisUpdate(String, usize, Vec<Evaluation>),
+    EvaluationUpdate(String, i32),
+    /// Moves the keyboard cursor by rows and columns.
+    MoveCursor(isize, isize),
+    PlayCursor,
+    Undo,
+    Redo,
+    NewGame,
+    PlayerName(StoneColor, String),
+    ReviewReady(Vec<Annotation>),
+    LanAddress(String),
+    /// Only meaningful for the connection it came from, so never serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Network(net::Event),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MenuItem {
     ChooseColor(StoneColor),
+    ChooseMode(Mode),
+    ChooseDifficulty(Difficulty),
+    ToggleLegalMoves,
+    ToggleFlipCounts,
+    ToggleLastMove,
+    ToggleEvaluation,
+    ToggleAnimations,
+    ChooseTimeControl(TimeControl),
+    ChooseDisplaySize(DisplaySize),
+    NextTheme,
+    ToggleCoordinates,
+    ToggleAccessibility,
+    ToggleDiscSymbols,
+    ChooseUiScale(u32),
+    ChooseLanguage(Language),
+    OpenSettings,
+    CloseSettings,
+    ChooseAnimationSpeed(AnimationSpeed),
     Play,
+    Continue,
+    HostLan,
+    JoinLan,
+    WatchLan,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Menu {
-    chosen_color: StoneColor,
-    play_pressed: bool,
-}
-impl Menu {
-    fn new() -> Self {
-        Menu {
-            chosen_color: StoneColor::Black,
-            play_pressed: false,
-        }
-    }
-}
-
-fn menu(game: &Game) -> Container<Message> {
-    container(
-        row![
-            button("Play")
-                .on_press(Message::MenuMessage(MenuItem::Play))
-                .style(theme::Button::Text)
-                .height(Length::Fixed(100.0))
-                .width(Length::Fixed(100.0)),
-            match game.menu.chosen_color {
-                StoneColor::Black => button(circle(40.0, iced::Color::BLACK)).on_press(
-                    Message::MenuMessage(MenuItem::ChooseColor(StoneColor::White))
-                ),
-                StoneColor::White => button(circle(40.0, iced::Color::WHITE)).on_press(
-                    Message::MenuMessage(MenuItem::ChooseColor(StoneColor::Black))
-                ),
-            }
-            .style(theme::Button::Positive)
-            .padding(10)
-            .height(Length::Fixed(100.0))
-            .width(Length::Fixed(100.0))
-        ]
-        .spacing(10),
-    )
-    .center_x()
-    .center_y()
-}
-
-fn playfield(game: &Game) -> Container<Message> {
-    let (white_stones, black_stones) = (game.game_board.white_count, game.game_board.black_count);
-    let tilebutton =
-        |row: usize, column: usize| match game.game_board.board[pair_to_index(row, column)].0 {
-            Some(StoneColor::Black) => button(circle(30.0, Color::BLACK))
-                .on_press(Message::EmptyPressed(row, column))
-                .style(theme::Button::Positive),
-            Some(StoneColor::White) => button(circle(30.0, Color::WHITE))
-                .on_press(Message::EmptyPressed(row, column))
-                .style(theme::Button::Positive),
-            None => button(circle(30.0, Color::TRANSPARENT))
-                .on_press(Message::EmptyPressed(row, column))
-                .style(theme::Button::Positive),
-        };
-    let playboard = (0..WIDTH).fold(Row::new(), |acc, column| {
-        let new_column = (0..HEIGHT).fold(Column::new(), |acc2, row| {
-            acc2.push(tilebutton(row, column))
-        });
-        acc.push(new_column.spacing(2).align_items(Alignment::Center))
-    });
-
-    container(
-        column![
-            row![button("RESET")
-                .on_press(Message::Reset)
-                .style(theme::Button::Destructive),]
-            .padding(20)
-            .align_items(Alignment::Center),
-            playboard.spacing(2).align_items(Alignment::Center),
-            row![text(format!(
-                "White:{white_stones}       Black:{black_stones}"
-            ))]
-            .padding(20)
-            .align_items(Alignment::Center),
-            row![text(match game.game_board.win {
-                GameOutcome::Win(StoneColor::White) => {
-                    "White wins!"
-                }
-                GameOutcome::Win(StoneColor::Black) => {
-                    "Black wins!"
-                }
-                GameOutcome::Draw => {
-                    "Draw!"
-                }
-                GameOutcome::InProgress => {
-                    "Awaiting results..."
-                }
-            })]
-        ]
-        .padding(20)
-        .align_items(Alignment::Center),
-    )
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameOverItem {
+    Rematch,
+    SwapColors,
+    Review,
+    Results,
+    BackToMenu,
 }
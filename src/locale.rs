@@ -0,0 +1,153 @@
+use crate::{Paths, StoneColor};
+use std::{collections::HashMap, fmt::Display, fs, path::PathBuf};
+
+/// Languages the interface is translated into.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Language {
+    #[default]
+    English,
+    Polish,
+    German,
+    Japanese,
+}
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::Polish,
+        Language::German,
+        Language::Japanese,
+    ];
+
+    /// ISO 639-1 code, also the catalog's file name.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Polish => "pl",
+            Self::German => "de",
+            Self::Japanese => "ja",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    /// The language's name for itself, as shown in the picker.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Polish => "Polski",
+            Self::German => "Deutsch",
+            Self::Japanese => "日本語",
+        }
+    }
+
+    /// The next language in the picker, wrapping around.
+    pub fn next(&self) -> Language {
+        let at = Self::ALL.iter().position(|language| language == self);
+        Self::ALL[at.map_or(0, |at| (at + 1) % Self::ALL.len())]
+    }
+
+    /// The catalog shipped with the game, from `locales/<code>.toml`.
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::English => include_str!("../locales/en.toml"),
+            Self::Polish => include_str!("../locales/pl.toml"),
+            Self::German => include_str!("../locales/de.toml"),
+            Self::Japanese => include_str!("../locales/ja.toml"),
+        }
+    }
+}
+
+/// Every string the interface shows, by key, in one language.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+impl Catalog {
+    /// `language`'s shipped catalog, falling back to English for anything missing.
+    pub fn built_in(language: Language) -> Catalog {
+        let shipped = |language: Language| {
+            parse_catalog(language.source()).expect("the shipped catalogs are valid TOML")
+        };
+        let mut messages = shipped(Language::English);
+        messages.extend(shipped(language));
+        Catalog { messages }
+    }
+
+    /// [`Catalog::built_in`] with the user's overrides on top.
+    pub fn new(language: Language, paths: &Paths) -> Catalog {
+        let mut catalog = Catalog::built_in(language);
+        if let Some(contents) =
+            overrides_path(paths, language).and_then(|path| fs::read_to_string(path).ok())
+        {
+            match parse_catalog(&contents) {
+                Ok(overrides) => catalog.messages.extend(overrides),
+                Err(error) => eprintln!("Couldn't read your {} messages: {error}", language.code()),
+            }
+        }
+        catalog
+    }
+
+    /// The message for `key`, or the key itself if no catalog has it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key).map_or(key, String::as_str)
+    }
+
+    /// The message for `key` with every `{name}` replaced by its value in `args`. The
+    /// message is read once from left to right, so braces in a value are left as they are.
+    pub fn fill(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut message = self.get(key);
+        let mut filled = String::with_capacity(message.len());
+        while let Some(open) = message.find('{') {
+            filled.push_str(&message[..open]);
+            let rest = &message[open..];
+            let value = rest.find('}').and_then(|close| {
+                let name = &rest[1..close];
+                let (_, value) = args.iter().find(|(arg, _)| *arg == name)?;
+                Some((close, value))
+            });
+            match value {
+                Some((close, value)) => {
+                    filled.push_str(&value.to_string());
+                    message = &rest[close + 1..];
+                }
+                None => {
+                    filled.push('{');
+                    message = &rest[1..];
+                }
+            }
+        }
+        filled.push_str(message);
+        filled
+    }
+
+    /// "Black" or "White".
+    pub fn color(&self, color: StoneColor) -> &str {
+        match color {
+            StoneColor::Black => self.get("black"),
+            StoneColor::White => self.get("white"),
+        }
+    }
+}
+
+/// Reads a catalog: flat TOML with one `key = "message"` per line. Keys whose value
+/// isn't a string are left out.
+pub fn parse_catalog(contents: &str) -> Result<HashMap<String, String>, toml::de::Error> {
+    let table: toml::Table = contents.parse()?;
+    Ok(table
+        .into_iter()
+        .filter_map(|(key, message)| Some((key, message.as_str()?.to_owned())))
+        .collect())
+}
+
+/// `locales/<code>.toml` in the config directory, for fixing or finishing a translation.
+pub fn overrides_path(paths: &Paths, language: Language) -> Option<PathBuf> {
+    paths.config.as_ref().map(|dir| {
+        dir.join("locales")
+            .join(format!("{}.toml", language.code()))
+    })
+}
@@ -1,6 +1,21 @@
-use iced::{Sandbox, Settings};
+use iced::{Application, Settings, Size};
 use reversi_iced::*;
-mod test;
+use std::process;
 fn main() -> iced::Result {
-    Game::run(Settings::default())
+    let options = match cli::Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{}", cli::USAGE);
+            process::exit(2)
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let mut settings = Settings::with_flags(options.clone());
+    if let Some((width, height)) = options.window_size {
+        settings.window.size = Size::new(width as f32, height as f32)
+    }
+    Game::run(settings)
 }
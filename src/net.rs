@@ -0,0 +1,820 @@
+use crate::{
+    index_to_pair, parse_square,
+    save::{color_from_name, color_name, moves_from_string, moves_to_string},
+    square_name, Board, Difficulty, Message,
+    PlayerOrComputer::Player,
+    Ply, StoneColor,
+};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        mpsc::{self, TryRecvError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Goes up whenever a packet changes meaning. Peers on different versions refuse
+/// each other instead of playing on with different rules.
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// How often the connection thread looks for something to send.
+const POLL: Duration = Duration::from_millis(50);
+/// A quiet connection says something this often, so the other side knows it's alive.
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+/// Silence after which the other side counts as gone.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the joining side waits between attempts to get back in.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Which end of a LAN game this is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// Waits for the other player on this port.
+    Host(u16),
+    /// Connects to the host at this address, or to a room on a server as
+    /// `address/room`.
+    Join(String),
+    /// Connects like [`Role::Join`], but only to watch.
+    Watch(String),
+}
+
+/// Splits what was typed in to join a game into the address and, for a server with
+/// several rooms, the room after a slash: `192.168.1.20:7878/evening`.
+pub fn split_room(target: &str) -> (&str, Option<&str>) {
+    match target.split_once('/') {
+        Some((address, room)) if !room.is_empty() => (address, Some(room)),
+        Some((address, _)) => (address, None),
+        None => (target, None),
+    }
+}
+
+/// `target` with [`DEFAULT_PORT`] added if its address doesn't name a port.
+pub fn with_default_port(target: &str) -> String {
+    let (address, room) = split_room(target);
+    let address = match address
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+    {
+        true => address.to_owned(),
+        false => format!("{address}:{DEFAULT_PORT}"),
+    };
+    match room {
+        Some(room) => format!("{address}/{room}"),
+        None => address,
+    }
+}
+
+/// FNV-1a over the position string: the same on every machine and every build, unlike
+/// the standard library's hasher.
+pub fn position_hash(board: &Board) -> u64 {
+    board
+        .to_position_string()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// One line of the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    /// The first thing the joining side says, `HELLO` to play or `WATCH` to look on.
+    /// A server seats it in `room`; a LAN host has only the one game and doesn't look.
+    Hello {
+        version: u32,
+        room: Option<String>,
+        watch: bool,
+    },
+    /// The whole game, from the host: when the other side joins, after a desync and
+    /// for every new game. `color` is the one the receiver plays.
+    Game {
+        color: StoneColor,
+        opening: Board,
+        history: Vec<Ply>,
+    },
+    /// A move made `plies` into the game, and the hash of the position it led to.
+    Move {
+        plies: usize,
+        square: usize,
+        hash: u64,
+    },
+    /// Asks the host for the whole game again.
+    Resync,
+    /// Asks for another game once this one is over, with the colors swapped.
+    Rematch,
+    Error(String),
+    /// Keeps a quiet connection from timing out.
+    Ping,
+}
+
+impl Packet {
+    /// A keyword and its fields, separated by spaces: `MOVE 4 d3 9f1c2e0a5b7d3c81`.
+    pub fn to_line(&self) -> String {
+        match self {
+            Packet::Hello {
+                version,
+                room,
+                watch,
+            } => {
+                let keyword = match watch {
+                    true => "WATCH",
+                    false => "HELLO",
+                };
+                match room {
+                    Some(room) => format!("{keyword} {version} {room}"),
+                    None => format!("{keyword} {version}"),
+                }
+            }
+            Packet::Game {
+                color,
+                opening,
+                history,
+            } => format!(
+                "GAME {} {} {}",
+                color_name(*color),
+                opening.to_position_string(),
+                moves_to_string(history)
+            )
+            .trim_end()
+            .to_owned(),
+            Packet::Move {
+                plies,
+                square,
+                hash,
+            } => format!("MOVE {plies} {} {hash:016x}", square_name(*square)),
+            Packet::Resync => String::from("RESYNC"),
+            Packet::Rematch => String::from("REMATCH"),
+            Packet::Error(message) => format!("ERROR {message}"),
+            Packet::Ping => String::from("PING"),
+        }
+    }
+
+    /// Inverse of [`Packet::to_line`]. Unknown keywords give `None`, so newer peers
+    /// can add packets that older ones skip.
+    pub fn from_line(line: &str) -> Option<Packet> {
+        let line = line.trim();
+        let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut fields = rest.split_whitespace();
+        let packet = match keyword {
+            "HELLO" | "WATCH" => Packet::Hello {
+                version: fields.next()?.parse().ok()?,
+                room: fields.next().map(str::to_owned),
+                watch: keyword == "WATCH",
+            },
+            "GAME" => {
+                let color = color_from_name(fields.next()?)?;
+                let opening =
+                    Board::from_position_string(&format!("{} {}", fields.next()?, fields.next()?))?;
+                let history = moves_from_string(&opening, &fields.collect::<Vec<_>>().join(" "))?;
+                Packet::Game {
+                    color,
+                    opening,
+                    history,
+                }
+            }
+            "MOVE" => Packet::Move {
+                plies: fields.next()?.parse().ok()?,
+                square: parse_square(fields.next()?)?,
+                hash: u64::from_str_radix(fields.next()?, 16).ok()?,
+            },
+            "RESYNC" => Packet::Resync,
+            "REMATCH" => Packet::Rematch,
+            "ERROR" => return Some(Packet::Error(rest.to_owned())),
+            "PING" => Packet::Ping,
+            _ => return None,
+        };
+        Some(packet)
+    }
+}
+
+/// What the connection thread tells the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The other side is there: it said hello to the host, or the host took the call.
+    Connected,
+    Received(Packet),
+    /// The connection broke or couldn't be made. The host waits for the other side
+    /// to come back and the joining side keeps trying.
+    Disconnected(String),
+    /// A spectator needs the whole game from the host: it has just come in, or
+    /// asked again.
+    Watcher(usize),
+}
+
+/// The game's end of a connection thread, which owns the socket. Dropping it stops
+/// the thread.
+pub struct Link {
+    role: Role,
+    outgoing: mpsc::Sender<Packet>,
+    /// Packets for the host's spectators: all of them, or the one numbered.
+    watched: mpsc::Sender<(Option<usize>, Packet)>,
+    events: Events,
+}
+
+/// What the connection thread has heard, for whoever is listening.
+#[derive(Debug, Clone)]
+pub struct Events(Arc<Mutex<mpsc::Receiver<Event>>>);
+impl Events {
+    /// Waits for the next event. `None` once the link is gone.
+    pub fn next(&self) -> Option<Event> {
+        self.0.lock().ok()?.recv().ok()
+    }
+
+    /// Like [`Events::next`], but gives up after `timeout`.
+    pub fn next_within(&self, timeout: Duration) -> Option<Event> {
+        self.0.lock().ok()?.recv_timeout(timeout).ok()
+    }
+}
+
+impl Link {
+    /// Starts listening straight away, so a port that's taken is reported here. Port 0
+    /// picks a free one; [`Link::role`] has the real one.
+    pub fn host(port: u16) -> io::Result<Link> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let port = listener.local_addr()?.port();
+        Ok(Link::spawn(
+            Role::Host(port),
+            move |outgoing, watched, events| serve(listener, outgoing, watched, events),
+        ))
+    }
+
+    /// Connects in the background, and again whenever the connection breaks. `target`
+    /// is an address, with a room after a slash for a server.
+    pub fn join(target: String) -> Link {
+        Link::spawn(Role::Join(target.clone()), move |outgoing, _, events| {
+            call(target, false, outgoing, events)
+        })
+    }
+
+    /// Like [`Link::join`], but to watch.
+    pub fn watch(target: String) -> Link {
+        Link::spawn(Role::Watch(target.clone()), move |outgoing, _, events| {
+            call(target, true, outgoing, events)
+        })
+    }
+
+    pub fn open(role: Role) -> io::Result<Link> {
+        match role {
+            Role::Host(port) => Link::host(port),
+            Role::Join(target) => Ok(Link::join(target)),
+            Role::Watch(target) => Ok(Link::watch(target)),
+        }
+    }
+
+    fn spawn(
+        role: Role,
+        run: impl FnOnce(
+                mpsc::Receiver<Packet>,
+                mpsc::Receiver<(Option<usize>, Packet)>,
+                mpsc::Sender<Event>,
+            ) + Send
+            + 'static,
+    ) -> Link {
+        let (outgoing, packets) = mpsc::channel();
+        let (watched, watched_packets) = mpsc::channel();
+        let (sender, events) = mpsc::channel();
+        thread::spawn(move || run(packets, watched_packets, sender));
+        Link {
+            role,
+            outgoing,
+            watched,
+            events: Events(Arc::new(Mutex::new(events))),
+        }
+    }
+
+    pub fn role(&self) -> &Role {
+        &self.role
+    }
+
+    /// Queues `packet` for the other side. It's dropped if nobody is connected.
+    pub fn send(&self, packet: Packet) {
+        let _ = self.outgoing.send(packet);
+    }
+
+    /// Queues `packet` for everyone watching the host's game.
+    pub fn tell_spectators(&self, packet: Packet) {
+        let _ = self.watched.send((None, packet));
+    }
+
+    /// Queues `packet` for the spectator numbered `spectator` in [`Event::Watcher`].
+    pub fn tell_spectator(&self, spectator: usize, packet: Packet) {
+        let _ = self.watched.send((Some(spectator), packet));
+    }
+
+    pub fn events(&self) -> Events {
+        self.events.clone()
+    }
+}
+
+/// A socket read a line at a time.
+pub(crate) struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    /// The part of a line that has come in so far.
+    partial: Vec<u8>,
+    heard: Instant,
+    said: Instant,
+}
+
+impl Connection {
+    pub(crate) fn new(stream: TcpStream) -> io::Result<Connection> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(POLL))?;
+        let now = Instant::now();
+        Ok(Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            partial: Vec::new(),
+            heard: now,
+            said: now,
+        })
+    }
+
+    pub(crate) fn send(&mut self, packet: &Packet) -> io::Result<()> {
+        writeln!(self.writer, "{}", packet.to_line())?;
+        self.said = Instant::now();
+        Ok(())
+    }
+
+    /// The next packet, or `None` if no whole line came in for a while or the line
+    /// wasn't one this version knows.
+    fn receive(&mut self) -> io::Result<Option<Packet>> {
+        match self.reader.read_until(b'\n', &mut self.partial) {
+            Ok(0) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "closed by the other side",
+            )),
+            // The other side closed the connection halfway through a line.
+            Ok(_) if !self.partial.ends_with(b"\n") => Ok(None),
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&self.partial).into_owned();
+                self.partial.clear();
+                self.heard = Instant::now();
+                Ok(Packet::from_line(&line))
+            }
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                match self.heard.elapsed() > TIMEOUT {
+                    true => Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "nothing heard from the other side",
+                    )),
+                    false => Ok(None),
+                }
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Sends what comes in on `outgoing` and hands what the other side says to
+    /// `deliver`, until the connection breaks. Returns `Ok` once `outgoing` is closed or
+    /// `deliver` says to stop.
+    pub(crate) fn exchange(
+        &mut self,
+        outgoing: &mpsc::Receiver<Packet>,
+        mut deliver: impl FnMut(Packet) -> bool,
+    ) -> io::Result<()> {
+        loop {
+            loop {
+                match outgoing.try_recv() {
+                    Ok(packet) => self.send(&packet)?,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return Ok(()),
+                }
+            }
+            if self.said.elapsed() > PING_INTERVAL {
+                self.send(&Packet::Ping)?
+            }
+            match self.receive()? {
+                None | Some(Packet::Ping) => {}
+                Some(packet) => {
+                    if !deliver(packet) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Throws away whatever the game queued while nobody was connected. Returns whether
+/// the game has dropped its [`Link`].
+fn closed(outgoing: &mpsc::Receiver<Packet>) -> bool {
+    loop {
+        match outgoing.try_recv() {
+            Ok(_) => {}
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => return true,
+        }
+    }
+}
+
+/// What a connection to the host tells the host's connection thread.
+enum Visit {
+    Arrived {
+        watch: bool,
+        outbox: mpsc::Sender<Packet>,
+    },
+    Said(Packet),
+    Left(String),
+}
+
+/// The host's connection thread: one player at a time and any number of spectators,
+/// each on a thread of their own, with everything going through here.
+fn serve(
+    listener: TcpListener,
+    outgoing: mpsc::Receiver<Packet>,
+    watched: mpsc::Receiver<(Option<usize>, Packet)>,
+    events: mpsc::Sender<Event>,
+) {
+    if listener.set_nonblocking(true).is_err() {
+        return;
+    }
+    let (visits, visitors) = mpsc::channel();
+    let mut player: Option<(usize, mpsc::Sender<Packet>)> = None;
+    let mut spectators = HashMap::new();
+    for id in 0.. {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let visits = visits.clone();
+                thread::spawn(move || visit(id, stream, visits));
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL),
+            // Out of file descriptors, say, which can last a while; don't spin on it.
+            Err(error) => {
+                eprintln!("Couldn't take a connection: {error}");
+                thread::sleep(POLL)
+            }
+        }
+        while let Ok((from, visit)) = visitors.try_recv() {
+            let playing = player.as_ref().is_some_and(|(id, _)| *id == from);
+            let event = match visit {
+                Visit::Arrived {
+                    watch: false,
+                    outbox,
+                } if player.is_some() => {
+                    let _ = outbox.send(Packet::Error(String::from(
+                        "someone else is already playing",
+                    )));
+                    None
+                }
+                Visit::Arrived {
+                    watch: false,
+                    outbox,
+                } => {
+                    player = Some((from, outbox));
+                    Some(Event::Connected)
+                }
+                Visit::Arrived {
+                    watch: true,
+                    outbox,
+                } => {
+                    spectators.insert(from, outbox);
+                    Some(Event::Watcher(from))
+                }
+                Visit::Said(packet) if playing => Some(Event::Received(packet)),
+                Visit::Said(Packet::Resync) => Some(Event::Watcher(from)),
+                Visit::Said(_) => None,
+                Visit::Left(reason) if playing => {
+                    player = None;
+                    Some(Event::Disconnected(reason))
+                }
+                Visit::Left(_) => {
+                    spectators.remove(&from);
+                    None
+                }
+            };
+            if event.is_some_and(|event| events.send(event).is_err()) {
+                return;
+            }
+        }
+        loop {
+            match outgoing.try_recv() {
+                Ok(packet) => {
+                    if let Some((_, outbox)) = &player {
+                        let _ = outbox.send(packet);
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+        while let Ok((to, packet)) = watched.try_recv() {
+            for (_, outbox) in spectators
+                .iter()
+                .filter(|(id, _)| to.is_none_or(|to| to == **id))
+            {
+                let _ = outbox.send(packet.clone());
+            }
+        }
+    }
+}
+
+/// One connection to the host, from hello to goodbye.
+fn visit(id: usize, stream: TcpStream, visits: mpsc::Sender<(usize, Visit)>) {
+    let (outbox, outgoing) = mpsc::channel();
+    let result = Connection::new(stream).and_then(|mut connection| {
+        let (_, watch) = greet(&mut connection)?;
+        let _ = visits.send((id, Visit::Arrived { watch, outbox }));
+        connection.exchange(&outgoing, |packet| {
+            visits.send((id, Visit::Said(packet))).is_ok()
+        })
+    });
+    let reason = match result {
+        Ok(()) => String::from("closed by the host"),
+        Err(error) => error.to_string(),
+    };
+    let _ = visits.send((id, Visit::Left(reason)));
+}
+
+/// Waits for the joining side's hello, and turns it away if it speaks another version.
+/// Returns the room it asked for and whether it only watches.
+pub(crate) fn greet(connection: &mut Connection) -> io::Result<(Option<String>, bool)> {
+    let started = Instant::now();
+    while started.elapsed() < TIMEOUT {
+        match connection.receive()? {
+            Some(Packet::Hello {
+                version,
+                room,
+                watch,
+            }) if version == PROTOCOL_VERSION => return Ok((room, watch)),
+            Some(Packet::Hello { version, .. }) => {
+                let message = format!(
+                    "protocol version {version} isn't supported, the host speaks {PROTOCOL_VERSION}"
+                );
+                connection.send(&Packet::Error(message.clone()))?;
+                return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+            }
+            _ => {}
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "the other side never said hello",
+    ))
+}
+
+/// The joining side's connection thread: calls the host, and calls again whenever the
+/// connection breaks.
+fn call(
+    target: String,
+    watch: bool,
+    outgoing: mpsc::Receiver<Packet>,
+    events: mpsc::Sender<Event>,
+) {
+    let (address, room) = split_room(&target);
+    loop {
+        let result = connect(address).and_then(|mut connection| {
+            connection.send(&Packet::Hello {
+                version: PROTOCOL_VERSION,
+                room: room.map(str::to_owned),
+                watch,
+            })?;
+            if events.send(Event::Connected).is_err() {
+                return Ok(());
+            }
+            connection.exchange(&outgoing, |packet| {
+                events.send(Event::Received(packet)).is_ok()
+            })
+        });
+        match result {
+            Ok(()) => return,
+            Err(error) => {
+                if events.send(Event::Disconnected(error.to_string())).is_err() {
+                    return;
+                }
+            }
+        }
+        let retry = Instant::now() + RECONNECT_DELAY;
+        while Instant::now() < retry {
+            if closed(&outgoing) {
+                return;
+            }
+            thread::sleep(POLL)
+        }
+    }
+}
+
+fn connect(address: &str) -> io::Result<Connection> {
+    let address = address.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no such address: {address}"),
+        )
+    })?;
+    Connection::new(TcpStream::connect_timeout(&address, TIMEOUT)?)
+}
+
+/// How far along the connection to the other side is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// Hosting before anyone joined, or joining before the host sent the game.
+    Waiting,
+    Connected,
+    /// Out of step with the host, and waiting for the whole game again.
+    Resyncing,
+    /// The connection broke, and is waited for or retried.
+    Lost(String),
+    /// The host turned this side away.
+    Refused(String),
+}
+
+/// What the game should do about something the other side sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Update {
+    /// The other side's move, already checked to lead to the same position there.
+    Play(usize),
+    /// Take over the host's game.
+    Replace { opening: Board, history: Vec<Ply> },
+    /// The joining side wants another game, with the colors swapped.
+    Rematch,
+}
+
+/// One side of a LAN game. The host's game is the real one: whenever the two
+/// disagree, the joining side takes the host's over.
+pub struct Session {
+    link: Link,
+    /// The color played on this side. The joining side learns it from the host.
+    pub color: StoneColor,
+    pub status: Status,
+}
+
+impl Session {
+    pub fn open(role: Role, color: StoneColor) -> io::Result<Session> {
+        Ok(Session {
+            link: Link::open(role)?,
+            color,
+            status: Status::Waiting,
+        })
+    }
+
+    pub fn role(&self) -> &Role {
+        self.link.role()
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self.role(), Role::Host(_))
+    }
+
+    pub fn is_spectator(&self) -> bool {
+        matches!(self.role(), Role::Watch(_))
+    }
+
+    pub fn events(&self) -> Events {
+        self.link.events()
+    }
+
+    /// Whether a move may be made on this side: only on its own turn, only while
+    /// the other side is there to hear about it, and never by a spectator.
+    pub fn can_move(&self, board: &Board) -> bool {
+        self.status == Status::Connected && board.turn == self.color && !self.is_spectator()
+    }
+
+    /// Tells the other side about a move made here, which took the game from `plies`
+    /// plies to `history`. Moves the other side made are left out, but the host's
+    /// spectators hear about every move.
+    pub fn played(&self, plies: usize, history: &[Ply], board: &Board) {
+        let Some((color, square)) = history[plies..]
+            .iter()
+            .find_map(|ply| Some((ply.color, ply.square?)))
+        else {
+            return;
+        };
+        let packet = Packet::Move {
+            plies,
+            square,
+            hash: position_hash(board),
+        };
+        if self.is_host() {
+            self.link.tell_spectators(packet.clone())
+        }
+        if !self.is_spectator() && color == self.color {
+            self.link.send(packet)
+        }
+    }
+
+    /// Sends the whole game over, to the other side and to any spectators. Only the
+    /// host's game counts, so this does nothing on the joining side.
+    pub fn send_game(&self, opening: &Board, history: &[Ply]) {
+        if self.is_host() {
+            self.link.send(self.game(opening, history));
+            self.link.tell_spectators(self.game(opening, history))
+        }
+    }
+
+    /// Asks for the next game, which only the host or a server can start. Does
+    /// nothing on the host's side, which starts it itself.
+    pub fn rematch(&self) {
+        if !self.is_host() && !self.is_spectator() {
+            self.link.send(Packet::Rematch)
+        }
+    }
+
+    /// The host's game, as the other side gets it.
+    fn game(&self, opening: &Board, history: &[Ply]) -> Packet {
+        Packet::Game {
+            color: self.color.reverse(),
+            opening: opening.clone(),
+            history: history.to_vec(),
+        }
+    }
+
+    /// Deals with `event`, given the game on this side.
+    pub fn handle(
+        &mut self,
+        event: Event,
+        opening: &Board,
+        history: &[Ply],
+        board: &Board,
+    ) -> Option<Update> {
+        match event {
+            Event::Connected if self.is_host() => {
+                self.status = Status::Connected;
+                self.send_game(opening, history)
+            }
+            // The joining side is only ready once the game has arrived.
+            Event::Connected => {}
+            // A refusal says more than the broken connection that follows it.
+            Event::Disconnected(_) if matches!(self.status, Status::Refused(_)) => {}
+            Event::Disconnected(reason) => self.status = Status::Lost(reason),
+            Event::Watcher(spectator) => self
+                .link
+                .tell_spectator(spectator, self.game(opening, history)),
+            Event::Received(packet) => match packet {
+                Packet::Game {
+                    color,
+                    opening,
+                    history,
+                } if !self.is_host() => {
+                    self.color = color;
+                    self.status = Status::Connected;
+                    return Some(Update::Replace { opening, history });
+                }
+                Packet::Move {
+                    plies,
+                    square,
+                    hash,
+                } => match self.check(plies, square, hash, history, board) {
+                    true => return Some(Update::Play(square)),
+                    false => self.desync(opening, history),
+                },
+                Packet::Resync => self.send_game(opening, history),
+                Packet::Rematch if self.is_host() => return Some(Update::Rematch),
+                Packet::Error(message) => self.status = Status::Refused(message),
+                _ => {}
+            },
+        }
+        None
+    }
+
+    /// Whether the other side's move fits the game here: made at the same point, on
+    /// its turn, and ending up in the same position.
+    fn check(
+        &self,
+        plies: usize,
+        square: usize,
+        hash: u64,
+        history: &[Ply],
+        board: &Board,
+    ) -> bool {
+        // A spectator takes every move as it comes.
+        let color = match self.is_spectator() {
+            true => board.turn,
+            false => self.color.reverse(),
+        };
+        if plies != history.len() || board.turn != color {
+            return false;
+        }
+        let (mut board, mut history) = (board.clone(), history.to_vec());
+        let (row, column) = index_to_pair(square);
+        board.colored_move_recorded(
+            Message::EmptyPressed(row, column),
+            Player,
+            Player,
+            color,
+            Difficulty::Easy,
+            &mut history,
+        );
+        history.len() > plies && position_hash(&board) == hash
+    }
+
+    fn desync(&mut self, opening: &Board, history: &[Ply]) {
+        match self.is_host() {
+            true => self.send_game(opening, history),
+            false => {
+                self.status = Status::Resyncing;
+                self.link.send(Packet::Resync)
+            }
+        }
+    }
+}
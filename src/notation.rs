@@ -0,0 +1,135 @@
+use crate::{
+    index_to_pair, pair_to_index, Board, Catalog, GameOutcome, Ply, StoneColor, Tile, HEIGHT, WIDTH,
+};
+
+/// Name of a square in the usual a–h / 1–8 notation, columns first: `d3`.
+pub fn square_name(id: usize) -> String {
+    let (row, column) = index_to_pair(id);
+    format!("{}{}", (b'a' + column as u8) as char, row + 1)
+}
+
+/// Inverse of [`square_name`]. Accepts upper case column letters too.
+pub fn parse_square(name: &str) -> Option<usize> {
+    let mut chars = name.chars();
+    let column = chars.next()?.to_ascii_lowercase();
+    if !column.is_ascii_lowercase() {
+        return None;
+    }
+    let column = (column as u8 - b'a') as usize;
+    let row = match chars.as_str().as_bytes() {
+        &[rank @ b'1'..=b'9'] => (rank - b'1') as usize,
+        _ => return None,
+    };
+    if row < HEIGHT && column < WIDTH {
+        Some(pair_to_index(row, column))
+    } else {
+        None
+    }
+}
+
+/// One sentence about the last ply of `history` and what comes next on `board`, the
+/// position after it, e.g. `Black d3 flips d4. White to move.` in English.
+pub fn status_line(history: &[Ply], board: &Board, catalog: &Catalog) -> String {
+    let last = match history.last() {
+        None => catalog.get("status_new_game").to_owned(),
+        Some(ply) => match ply.square {
+            None => catalog.fill("status_pass", &[("color", &catalog.color(ply.color))]),
+            Some(square) => {
+                let flipped: Vec<String> = ply.flipped.iter().map(|&id| square_name(id)).collect();
+                catalog.fill(
+                    "status_move",
+                    &[
+                        ("color", &catalog.color(ply.color)),
+                        ("square", &square_name(square)),
+                        ("flipped", &flipped.join(" ")),
+                    ],
+                )
+            }
+        },
+    };
+    let (black, white) = (board.black_count, board.white_count);
+    let next = match board.win {
+        GameOutcome::InProgress => {
+            catalog.fill("status_to_move", &[("color", &catalog.color(board.turn))])
+        }
+        GameOutcome::Win(color) => {
+            let (winner, loser) = match color {
+                StoneColor::Black => (black, white),
+                StoneColor::White => (white, black),
+            };
+            catalog.fill(
+                "status_win",
+                &[
+                    ("color", &catalog.color(color)),
+                    ("winner", &winner),
+                    ("loser", &loser),
+                ],
+            )
+        }
+        GameOutcome::Draw => catalog.fill("status_draw", &[("black", &black), ("white", &white)]),
+    };
+    format!("{last} {next}")
+}
+
+impl Board {
+    /// The board as 64 characters (`X` black, `O` white, `-` empty), followed by
+    /// a space and the side to move.
+    pub fn to_position_string(&self) -> String {
+        let mut out: String = self
+            .board
+            .iter()
+            .map(|tile| match tile.0 {
+                Some(StoneColor::Black) => 'X',
+                Some(StoneColor::White) => 'O',
+                None => '-',
+            })
+            .collect();
+        out.push(' ');
+        out.push(match self.turn {
+            StoneColor::Black => 'X',
+            StoneColor::White => 'O',
+        });
+        out
+    }
+
+    pub fn from_position_string(position: &str) -> Option<Board> {
+        let (stones, turn) = position.trim().split_once(' ')?;
+        if stones.chars().count() != WIDTH * HEIGHT {
+            return None;
+        }
+        let mut board = Board::make_empty();
+        for (id, stone) in stones.chars().enumerate() {
+            match stone {
+                'X' | 'x' | '*' => board.place_stone(id, StoneColor::Black),
+                'O' | 'o' => board.place_stone(id, StoneColor::White),
+                '-' | '.' => board.board[id] = Tile(None),
+                _ => return None,
+            }
+        }
+        board.turn = match turn.trim() {
+            "X" | "x" | "*" => StoneColor::Black,
+            "O" | "o" => StoneColor::White,
+            _ => return None,
+        };
+        board.win = board.wincheck();
+        Some(board)
+    }
+}
+
+/// Boards go over the wire as their position string; the outcome and disc counts
+/// are worked out again on the way back in.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_position_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Board, D::Error> {
+        let position = String::deserialize(deserializer)?;
+        Board::from_position_string(&position)
+            .ok_or_else(|| serde::de::Error::custom(format!("not a position string: {position}")))
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+/// The game's own directories, where everything it keeps between runs goes. `None`
+/// where the system has no such directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    /// Settings, themes and fixes to translations.
+    pub config: Option<PathBuf>,
+    /// The game in progress.
+    pub data: Option<PathBuf>,
+}
+
+impl Paths {
+    /// `reversi_iced` in the user's config and data directories, e.g.
+    /// `~/.config/reversi_iced` and `~/.local/share/reversi_iced` on Linux.
+    pub fn user() -> Paths {
+        Paths {
+            config: dirs::config_dir().map(|dir| dir.join("reversi_iced")),
+            data: dirs::data_dir().map(|dir| dir.join("reversi_iced")),
+        }
+    }
+}
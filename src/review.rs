@@ -0,0 +1,67 @@
+use crate::{Board, Evaluation, Ply, StoneColor};
+
+/// What the engine thinks of one move of a finished game.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
+    /// Index of the move in the game history.
+    pub ply: usize,
+    pub color: StoneColor,
+    pub played: Evaluation,
+    /// The move the engine would have played instead.
+    pub best: Evaluation,
+    /// Discs given away compared with `best`, never negative.
+    pub loss: i32,
+    /// Whether `loss` went over the threshold the review was run with.
+    pub mistake: bool,
+}
+impl Annotation {
+    /// The expected disc differential after this move, from Black's point of view,
+    /// so a whole game can be plotted on one axis.
+    pub fn black_evaluation(&self) -> i32 {
+        match self.color {
+            StoneColor::Black => self.played.differential,
+            StoneColor::White => -self.played.differential,
+        }
+    }
+}
+
+/// Searches every position of `history` at `depth` and compares the move played
+/// with the engine's choice, flagging the ones that lost more than `threshold` discs.
+/// Passes are skipped.
+pub fn review_game(history: &[Ply], depth: usize, threshold: i32) -> Vec<Annotation> {
+    review_game_from(&Board::new(), history, depth, threshold)
+}
+
+/// [`review_game`] for a game that started from `opening` rather than the usual four discs.
+pub fn review_game_from(
+    opening: &Board,
+    history: &[Ply],
+    depth: usize,
+    threshold: i32,
+) -> Vec<Annotation> {
+    let mut board = opening.clone();
+    let mut annotations = Vec::new();
+    for (ply, played) in history.iter().enumerate() {
+        let Some(square) = played.square else {
+            continue;
+        };
+        let evaluations = board.evaluate_moves(played.color, depth);
+        let found = evaluations
+            .iter()
+            .find(|evaluation| evaluation.square == square);
+        if let (Some(&best), Some(&played_evaluation)) = (evaluations.first(), found) {
+            let loss = (best.differential - played_evaluation.differential).max(0);
+            annotations.push(Annotation {
+                ply,
+                color: played.color,
+                played: played_evaluation,
+                best,
+                loss,
+                mistake: loss > threshold,
+            });
+        }
+        board.play_move(square, played.color);
+    }
+    annotations
+}
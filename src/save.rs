@@ -0,0 +1,181 @@
+use crate::{parse_square, square_name, Board, Difficulty, Mode, Paths, Ply, StoneColor};
+use std::{fs, io, path::PathBuf};
+
+/// Everything needed to pick a game back up after the window was closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedGame {
+    /// Where the game started, usually [`Board::new`].
+    pub opening: Board,
+    pub board: Board,
+    pub history: Vec<Ply>,
+    pub chosen_color: StoneColor,
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+}
+
+impl SavedGame {
+    /// Plain `key value` lines. Moves are written in a–h/1–8 notation (or `pass`),
+    /// upper case for Black and lower case for White.
+    pub fn to_file_string(&self) -> String {
+        let opening = match self.opening == Board::new() {
+            true => String::new(),
+            false => format!("opening {}\n", self.opening.to_position_string()),
+        };
+        format!(
+            "color {}\nmode {}\ndifficulty {}\n{opening}position {}\nmoves {}\n",
+            color_name(self.chosen_color),
+            mode_name(self.mode),
+            difficulty_name(self.difficulty),
+            self.board.to_position_string(),
+            moves_to_string(&self.history)
+        )
+    }
+
+    pub fn from_file_string(contents: &str) -> Option<SavedGame> {
+        let (mut position, mut history, mut chosen_color, mut mode, mut difficulty) =
+            (None, Vec::new(), None, None, None);
+        let mut opening = Board::new();
+        for line in contents.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "color" => chosen_color = color_from_name(value),
+                "mode" => mode = mode_from_name(value),
+                "difficulty" => difficulty = difficulty_from_name(value),
+                "opening" => opening = Board::from_position_string(value)?,
+                "position" => position = Board::from_position_string(value),
+                "moves" => history = moves_from_string(&opening, value)?,
+                _ => {}
+            }
+        }
+        // The position is written out too, so a file whose moves lead somewhere else
+        // has been damaged or edited by hand.
+        let board = opening.replay(&history);
+        if board.to_position_string() != position?.to_position_string() {
+            return None;
+        }
+        Some(SavedGame {
+            opening,
+            board,
+            history,
+            chosen_color: chosen_color?,
+            mode: mode?,
+            difficulty: difficulty?,
+        })
+    }
+}
+
+/// Moves in a–h/1–8 notation (or `pass`), upper case for Black and lower case for
+/// White, separated by spaces.
+pub(crate) fn moves_to_string(history: &[Ply]) -> String {
+    let moves: Vec<String> = history
+        .iter()
+        .map(|ply| {
+            let name = match ply.square {
+                Some(square) => square_name(square),
+                None => String::from("pass"),
+            };
+            match ply.color {
+                StoneColor::Black => name.to_uppercase(),
+                StoneColor::White => name,
+            }
+        })
+        .collect();
+    moves.join(" ")
+}
+
+/// Inverse of [`moves_to_string`] for a game that started from `opening`. Replaying
+/// from there recovers which stones each move turned over. Every ply has to be the
+/// side to move's, and a pass has to be forced.
+pub(crate) fn moves_from_string(opening: &Board, moves: &str) -> Option<Vec<Ply>> {
+    let mut replay = opening.clone();
+    let mut turn = opening.turn;
+    let mut history = Vec::new();
+    for name in moves.split_whitespace() {
+        let color = match name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            true => StoneColor::Black,
+            false => StoneColor::White,
+        };
+        if color != turn {
+            return None;
+        }
+        history.push(match name.eq_ignore_ascii_case("pass") {
+            true if replay.must_pass(color) => Ply::pass(color),
+            true => return None,
+            false => replay.play_move(parse_square(name)?, color)?,
+        });
+        turn = color.reverse();
+    }
+    Some(history)
+}
+
+pub(crate) fn color_name(color: StoneColor) -> &'static str {
+    match color {
+        StoneColor::Black => "black",
+        StoneColor::White => "white",
+    }
+}
+pub(crate) fn color_from_name(name: &str) -> Option<StoneColor> {
+    match name {
+        "black" => Some(StoneColor::Black),
+        "white" => Some(StoneColor::White),
+        _ => None,
+    }
+}
+pub(crate) fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::VsComputer => "computer",
+        Mode::TwoPlayers => "two-players",
+        Mode::Analysis => "analysis",
+    }
+}
+pub(crate) fn mode_from_name(name: &str) -> Option<Mode> {
+    match name {
+        "computer" => Some(Mode::VsComputer),
+        "two-players" => Some(Mode::TwoPlayers),
+        "analysis" => Some(Mode::Analysis),
+        _ => None,
+    }
+}
+pub(crate) fn difficulty_name(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "easy",
+        Difficulty::Medium => "medium",
+        Difficulty::Hard => "hard",
+    }
+}
+pub(crate) fn difficulty_from_name(name: &str) -> Option<Difficulty> {
+    match name {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        _ => None,
+    }
+}
+
+/// `savegame` in the data directory, e.g. `~/.local/share/reversi_iced/savegame` on Linux.
+pub fn save_path(paths: &Paths) -> Option<PathBuf> {
+    paths.data.as_ref().map(|dir| dir.join("savegame"))
+}
+
+pub fn exists(paths: &Paths) -> bool {
+    save_path(paths).is_some_and(|path| path.is_file())
+}
+
+pub fn store(paths: &Paths, game: &SavedGame) -> io::Result<()> {
+    let path = save_path(paths).ok_or(io::ErrorKind::NotFound)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, game.to_file_string())
+}
+
+pub fn load(paths: &Paths) -> Option<SavedGame> {
+    SavedGame::from_file_string(&fs::read_to_string(save_path(paths)?).ok()?)
+}
+
+pub fn remove(paths: &Paths) {
+    if let Some(path) = save_path(paths) {
+        let _ = fs::remove_file(path);
+    }
+}
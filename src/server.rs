@@ -0,0 +1,523 @@
+use crate::{
+    index_to_pair,
+    net::{greet, position_hash, Connection, Packet, DEFAULT_PORT},
+    save::{difficulty_from_name, SavedGame},
+    Board, Difficulty, GameOutcome, Message, Mode,
+    PlayerOrComputer::Player,
+    Ply, StoneColor,
+};
+use std::{
+    collections::HashMap,
+    fs, io,
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const USAGE: &str = "\
+Usage: reversi_server [OPTIONS]
+
+Options:
+  --port <PORT>     Where to listen, 7878 by default
+  --games <DIR>     Where finished games are kept, by default reversi_iced/games
+                    in the data directory
+  -h, --help        Print this and exit
+
+Players join a room with --join ADDRESS/ROOM, or just ADDRESS for the room called
+\"default\". The first to join a room plays Black and the second White. In a room
+whose name starts with bot-easy, bot-medium or bot-hard the computer plays White.
+Once a game is over either player can ask for a rematch, which swaps the colors.
+Anyone can look on with --watch ADDRESS/ROOM.";
+
+/// The room a player lands in when they don't name one.
+pub const DEFAULT_ROOM: &str = "default";
+
+/// How long to wait after the listener fails to take a connection, which can keep
+/// happening for a while, e.g. when the server is out of file descriptors.
+const BACKOFF: Duration = Duration::from_millis(100);
+
+/// What the server was started with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub port: u16,
+    pub games: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Options {
+    /// Reads the arguments after the program name, the same way the game does.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            port: DEFAULT_PORT,
+            games: None,
+            help: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+            match flag.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--port" | "--games" => {
+                    let value = inline
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("{flag} needs a value"))?;
+                    match flag.as_str() {
+                        "--port" => {
+                            options.port = value
+                                .parse()
+                                .map_err(|_| format!("invalid value for {flag}: {value}"))?
+                        }
+                        _ => options.games = Some(PathBuf::from(value)),
+                    }
+                }
+                _ => return Err(format!("unknown argument: {flag}")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// `<data dir>/reversi_iced/games`, next to the save file.
+pub fn games_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("reversi_iced").join("games"))
+}
+
+/// Who sits at one color of a room.
+enum Seat {
+    Empty,
+    /// A connected player, by connection number, reached through their connection's
+    /// outbox.
+    Player(usize, mpsc::Sender<Packet>),
+    Bot(Difficulty),
+}
+
+/// One game on the server. Its board is the real one: a player's move only counts
+/// once [`Board::make_move`] has taken it here.
+struct Room {
+    name: String,
+    board: Board,
+    history: Vec<Ply>,
+    /// Black's seat, then White's.
+    seats: [Seat; 2],
+    /// Everyone watching, by connection number.
+    spectators: HashMap<usize, mpsc::Sender<Packet>>,
+    /// Where the game is written once it's over.
+    games: PathBuf,
+    /// Set when the last one out leaves. Whoever finds the room before it's taken
+    /// out makes a new one in its place.
+    closed: bool,
+    /// Wakes the computer's thread, in a room where the computer plays.
+    bot: Option<mpsc::Sender<()>>,
+}
+
+fn seat_index(color: StoneColor) -> usize {
+    match color {
+        StoneColor::Black => 0,
+        StoneColor::White => 1,
+    }
+}
+
+impl Room {
+    /// A room called `bot-<difficulty>`, or that followed by anything, has the
+    /// computer playing White.
+    fn new(name: String, games: PathBuf) -> Room {
+        let bot = name
+            .strip_prefix("bot-")
+            .and_then(|rest| difficulty_from_name(rest.split('-').next().unwrap_or(rest)));
+        Room {
+            name,
+            board: Board::new(),
+            history: Vec::new(),
+            seats: [Seat::Empty, bot.map_or(Seat::Empty, Seat::Bot)],
+            spectators: HashMap::new(),
+            games,
+            closed: false,
+            bot: None,
+        }
+    }
+
+    /// The color player number `id` has, if they have a seat here.
+    fn color_of(&self, id: usize) -> Option<StoneColor> {
+        [StoneColor::Black, StoneColor::White]
+            .into_iter()
+            .find(|&color| match self.seats[seat_index(color)] {
+                Seat::Player(seated, _) => seated == id,
+                _ => false,
+            })
+    }
+
+    fn send(&self, color: StoneColor, packet: Packet) {
+        if let Seat::Player(_, outbox) = &self.seats[seat_index(color)] {
+            let _ = outbox.send(packet);
+        }
+    }
+
+    /// Sends `packet` to everyone watching.
+    fn broadcast(&self, packet: &Packet) {
+        for outbox in self.spectators.values() {
+            let _ = outbox.send(packet.clone());
+        }
+    }
+
+    fn game(&self, color: StoneColor) -> Packet {
+        Packet::Game {
+            color,
+            opening: Board::new(),
+            history: self.history.clone(),
+        }
+    }
+
+    fn send_game(&self, color: StoneColor) {
+        self.send(color, self.game(color))
+    }
+
+    /// Seats player number `id` at the first free color and sends them the game.
+    /// `None` if the room is full.
+    fn enter(&mut self, id: usize, outbox: mpsc::Sender<Packet>) -> Option<StoneColor> {
+        let color = [StoneColor::Black, StoneColor::White]
+            .into_iter()
+            .find(|&color| matches!(self.seats[seat_index(color)], Seat::Empty))?;
+        self.seats[seat_index(color)] = Seat::Player(id, outbox);
+        self.send_game(color);
+        Some(color)
+    }
+
+    /// Lets spectator number `id` look on, starting with the game so far.
+    fn watch(&mut self, id: usize, outbox: mpsc::Sender<Packet>) {
+        let _ = outbox.send(self.game(StoneColor::Black));
+        self.spectators.insert(id, outbox);
+    }
+
+    /// Frees player number `id`'s seat. Returns whether that closed the room.
+    fn leave(&mut self, id: usize) -> bool {
+        if let Some(color) = self.color_of(id) {
+            self.seats[seat_index(color)] = Seat::Empty;
+        }
+        self.closed = !self.occupied();
+        self.closed
+    }
+
+    /// Lets spectator number `id` go. Returns whether that closed the room.
+    fn stop_watching(&mut self, id: usize) -> bool {
+        self.spectators.remove(&id);
+        self.closed = !self.occupied();
+        self.closed
+    }
+
+    fn occupied(&self) -> bool {
+        !self.spectators.is_empty()
+            || self
+                .seats
+                .iter()
+                .any(|seat| matches!(seat, Seat::Player(..)))
+    }
+
+    /// Deals with what player number `id` sent.
+    fn receive(&mut self, id: usize, packet: Packet) {
+        let Some(color) = self.color_of(id) else {
+            return;
+        };
+        match packet {
+            Packet::Move {
+                plies,
+                square,
+                hash,
+            } => {
+                if plies != self.history.len() || !self.play(color, square) {
+                    // Whatever the player thinks the game is, it isn't this one.
+                    self.send_game(color);
+                    return;
+                }
+                let hash_here = position_hash(&self.board);
+                let packet = Packet::Move {
+                    plies,
+                    square,
+                    hash: hash_here,
+                };
+                self.broadcast(&packet);
+                self.send(color.reverse(), packet);
+                if hash != hash_here {
+                    self.send_game(color)
+                }
+            }
+            Packet::Resync => self.send_game(color),
+            Packet::Rematch => self.rematch(),
+            _ => {}
+        }
+    }
+
+    /// Plays `square` for `color` if it's `color`'s turn and the move is legal.
+    fn play(&mut self, color: StoneColor, square: usize) -> bool {
+        let (row, column) = index_to_pair(square);
+        if self.board.win != GameOutcome::InProgress
+            || self.board.turn != color
+            || !self
+                .board
+                .clone()
+                .make_move(row, column, color, &mut Vec::with_capacity(8))
+        {
+            return false;
+        }
+        self.board.colored_move_recorded(
+            Message::EmptyPressed(row, column),
+            Player,
+            Player,
+            color,
+            Difficulty::Easy,
+            &mut self.history,
+        );
+        if self.board.win != GameOutcome::InProgress {
+            self.archive()
+        }
+        true
+    }
+
+    /// Starts the next game once this one is over, with the colors swapped, bot
+    /// and all.
+    fn rematch(&mut self) {
+        if self.board.win == GameOutcome::InProgress {
+            return;
+        }
+        self.seats.swap(0, 1);
+        self.board = Board::new();
+        self.history.clear();
+        self.send_game(StoneColor::Black);
+        self.send_game(StoneColor::White);
+        self.broadcast(&self.game(StoneColor::Black));
+    }
+
+    /// Has the computer look at the board, and move if it's its turn.
+    fn wake_bot(&self) {
+        if let Some(bot) = &self.bot {
+            let _ = bot.send(());
+        }
+    }
+
+    /// The board and the computer's strength, if it's the computer's turn.
+    fn bot_turn(&self) -> Option<(Board, Difficulty)> {
+        match self.seats[seat_index(self.board.turn)] {
+            Seat::Bot(difficulty) if self.board.win == GameOutcome::InProgress => {
+                Some((self.board.clone(), difficulty))
+            }
+            _ => None,
+        }
+    }
+
+    /// Plays the computer's move and tells everyone.
+    fn bot_plays(&mut self, square: usize) {
+        let plies = self.history.len();
+        self.play(self.board.turn, square);
+        let packet = Packet::Move {
+            plies,
+            square,
+            hash: position_hash(&self.board),
+        };
+        self.broadcast(&packet);
+        self.send(StoneColor::Black, packet.clone());
+        self.send(StoneColor::White, packet);
+    }
+
+    /// Writes the finished game to the games directory, in the save file format.
+    fn archive(&self) {
+        let (chosen_color, difficulty) = match self.seats {
+            [_, Seat::Bot(difficulty)] => (StoneColor::Black, Some(difficulty)),
+            [Seat::Bot(difficulty), _] => (StoneColor::White, Some(difficulty)),
+            _ => (StoneColor::Black, None),
+        };
+        let game = SavedGame {
+            opening: Board::new(),
+            board: self.board.clone(),
+            history: self.history.clone(),
+            chosen_color,
+            mode: match difficulty {
+                Some(_) => Mode::VsComputer,
+                None => Mode::TwoPlayers,
+            },
+            difficulty: difficulty.unwrap_or_default(),
+        };
+        let name: String = self
+            .name
+            .chars()
+            .map(
+                |c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    true => c,
+                    false => '_',
+                },
+            )
+            .collect();
+        let finished = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = self.games.join(format!("{name}-{finished}.txt"));
+        let written =
+            fs::create_dir_all(&self.games).and_then(|_| fs::write(&path, game.to_file_string()));
+        match written {
+            Ok(()) => eprintln!("{}: game over, saved to {}", self.name, path.display()),
+            Err(error) => eprintln!("{}: couldn't save the game: {error}", self.name),
+        }
+    }
+}
+
+type Rooms = Arc<Mutex<HashMap<String, Arc<Mutex<Room>>>>>;
+
+/// Many games at once, each in a room of its own, for any number of players.
+pub struct Server {
+    listener: TcpListener,
+    rooms: Rooms,
+    games: PathBuf,
+}
+
+impl Server {
+    /// Starts listening, keeping finished games in `games`. Port 0 picks a free one.
+    pub fn bind(port: u16, games: PathBuf) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(("0.0.0.0", port))?,
+            rooms: Arc::default(),
+            games,
+        })
+    }
+
+    pub fn port(&self) -> io::Result<u16> {
+        Ok(self.listener.local_addr()?.port())
+    }
+
+    /// Takes players and spectators in for good, each on a thread of its own.
+    pub fn run(&self) {
+        for id in 0.. {
+            let (stream, peer) = match self.listener.accept() {
+                Ok(accepted) => accepted,
+                Err(error) => {
+                    eprintln!("Couldn't take a connection: {error}");
+                    thread::sleep(BACKOFF);
+                    continue;
+                }
+            };
+            let (rooms, games) = (self.rooms.clone(), self.games.clone());
+            thread::spawn(move || {
+                if let Err(error) = attend(id, stream, rooms, games) {
+                    eprintln!("{peer}: {error}")
+                }
+            });
+        }
+    }
+}
+
+/// Connection number `id`, a player or a spectator, from hello to goodbye. The rooms
+/// lock is never held while waiting for a room.
+fn attend(id: usize, stream: TcpStream, rooms: Rooms, games: PathBuf) -> io::Result<()> {
+    let mut connection = Connection::new(stream)?;
+    let (name, watch) = greet(&mut connection)?;
+    let name = name.unwrap_or_else(|| DEFAULT_ROOM.to_owned());
+    let (outbox, outgoing) = mpsc::channel();
+    let (room, color) = loop {
+        let room = rooms
+            .lock()
+            .unwrap()
+            .entry(name.clone())
+            .or_insert_with(|| open(Room::new(name.clone(), games.clone())))
+            .clone();
+        let mut here = room.lock().unwrap();
+        if here.closed {
+            drop(here);
+            forget(&rooms, &name, &room);
+            continue;
+        }
+        let color = match watch {
+            true => {
+                here.watch(id, outbox.clone());
+                None
+            }
+            false => here.enter(id, outbox.clone()),
+        };
+        drop(here);
+        break (room, color);
+    };
+    if watch {
+        eprintln!("{name}: spectator {id} came");
+        // Spectators can only ask to see the game again.
+        let result = connection.exchange(&outgoing, |packet| {
+            if packet == Packet::Resync {
+                let room = room.lock().unwrap();
+                let _ = room.spectators[&id].send(room.game(StoneColor::Black));
+            }
+            true
+        });
+        if room.lock().unwrap().stop_watching(id) {
+            forget(&rooms, &name, &room)
+        }
+        eprintln!("{name}: spectator {id} went");
+        return result;
+    }
+    let Some(color) = color else {
+        return connection.send(&Packet::Error(format!("room {name} is full")));
+    };
+    eprintln!("{name}: player {id} joined as {color:?}");
+    room.lock().unwrap().wake_bot();
+    let result = connection.exchange(&outgoing, |packet| {
+        let mut room = room.lock().unwrap();
+        room.receive(id, packet);
+        room.wake_bot();
+        true
+    });
+    if room.lock().unwrap().leave(id) {
+        forget(&rooms, &name, &room)
+    }
+    eprintln!("{name}: player {id} left");
+    result
+}
+
+/// Opens `room`. Where the computer plays, it gets a thread of its own to think on,
+/// which stops once the room is gone, so no player's connection waits on a search.
+fn open(room: Room) -> Arc<Mutex<Room>> {
+    let has_bot = room.seats.iter().any(|seat| matches!(seat, Seat::Bot(_)));
+    let room = Arc::new(Mutex::new(room));
+    if has_bot {
+        let (bot, woken) = mpsc::channel();
+        room.lock().unwrap().bot = Some(bot);
+        let weak = Arc::downgrade(&room);
+        thread::spawn(move || {
+            for () in woken {
+                let Some(room) = weak.upgrade() else {
+                    return;
+                };
+                bot_moves(&room)
+            }
+        });
+    }
+    room
+}
+
+/// Lets the computer move for as long as it's its turn. It thinks on a copy of the
+/// board, so the room's players and spectators aren't kept waiting meanwhile.
+fn bot_moves(room: &Mutex<Room>) {
+    loop {
+        let Some((board, difficulty)) = room.lock().unwrap().bot_turn() else {
+            return;
+        };
+        let Some(square) = board.best_move(board.turn, difficulty.depth()) else {
+            return;
+        };
+        let mut room = room.lock().unwrap();
+        // A rematch may have started while the computer was thinking.
+        if room.board == board {
+            room.bot_plays(square)
+        }
+    }
+}
+
+/// Takes `room` out of the rooms, unless a new one has already taken its place.
+fn forget(rooms: &Rooms, name: &str, room: &Arc<Mutex<Room>>) {
+    let mut rooms = rooms.lock().unwrap();
+    if rooms
+        .get(name)
+        .is_some_and(|found| Arc::ptr_eq(found, room))
+    {
+        rooms.remove(name);
+    }
+}
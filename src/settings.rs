@@ -0,0 +1,279 @@
+use crate::{
+    clock::{MINUTES, SECONDS},
+    save::{
+        color_from_name, color_name, difficulty_from_name, difficulty_name, mode_from_name,
+        mode_name,
+    },
+    Difficulty, Language, Mode, Paths, StoneColor, TimeControl,
+};
+use std::{fs, io, path::PathBuf, time::Duration};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnimationSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+impl AnimationSpeed {
+    /// How long a single stone takes to appear or turn over.
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Slow => Duration::from_millis(400),
+            Self::Normal => Duration::from_millis(200),
+            Self::Fast => Duration::from_millis(100),
+        }
+    }
+    /// Delay between a stone turning over and the next one further along the line.
+    pub fn step(&self) -> Duration {
+        self.duration() / 3
+    }
+}
+
+/// Interface sizes to choose from, in percent.
+pub const UI_SCALES: [u32; 5] = [75, 100, 125, 150, 200];
+
+/// How large the board is drawn on screen. The board itself is always 8 by 8.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplaySize {
+    Small,
+    Medium,
+    Large,
+    /// As large as the window allows.
+    #[default]
+    Fit,
+}
+impl DisplaySize {
+    /// Side of the board canvas in pixels, or `None` to fill the space available.
+    pub fn pixels(&self) -> Option<f32> {
+        match self {
+            Self::Small => Some(360.0),
+            Self::Medium => Some(520.0),
+            Self::Large => Some(680.0),
+            Self::Fit => None,
+        }
+    }
+
+    /// Name in the settings file and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Small => "small",
+            Self::Medium => "medium",
+            Self::Large => "large",
+            Self::Fit => "fit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DisplaySize> {
+        match name {
+            "small" => Some(Self::Small),
+            "medium" => Some(Self::Medium),
+            "large" => Some(Self::Large),
+            "fit" => Some(Self::Fit),
+            _ => None,
+        }
+    }
+}
+
+/// Everything the settings page lets you choose, kept between runs.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Settings {
+    pub chosen_color: StoneColor,
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+    pub time_control: TimeControl,
+    pub display_size: DisplaySize,
+    /// Name of one of the built-in or user themes.
+    pub theme: String,
+    pub show_coordinates: bool,
+    pub show_legal_moves: bool,
+    pub show_flip_counts: bool,
+    pub show_last_move: bool,
+    /// Off by default so the bar doesn't give games against the computer away.
+    pub show_evaluation: bool,
+    pub animations: bool,
+    pub animation_speed: AnimationSpeed,
+    /// Empty to go by the color's name in the chosen language.
+    pub black_name: String,
+    pub white_name: String,
+    pub language: Language,
+    /// Contrasting outlines around discs and a written account of every move.
+    pub accessibility: bool,
+    /// A cross on black discs and a ring on white ones, so they don't rely on color.
+    pub disc_symbols: bool,
+    /// Size of the whole interface, in percent.
+    pub ui_scale: u32,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            chosen_color: StoneColor::Black,
+            mode: Mode::VsComputer,
+            difficulty: Difficulty::default(),
+            time_control: TimeControl::default(),
+            display_size: DisplaySize::default(),
+            theme: String::from("Classic"),
+            show_coordinates: true,
+            show_legal_moves: true,
+            show_flip_counts: false,
+            show_last_move: true,
+            show_evaluation: false,
+            animations: true,
+            animation_speed: AnimationSpeed::default(),
+            black_name: String::new(),
+            white_name: String::new(),
+            language: Language::default(),
+            accessibility: false,
+            disc_symbols: false,
+            ui_scale: 100,
+        }
+    }
+}
+
+impl Settings {
+    /// The name typed in for whoever plays `color`, possibly empty.
+    pub fn name(&self, color: StoneColor) -> &str {
+        match color {
+            StoneColor::Black => &self.black_name,
+            StoneColor::White => &self.white_name,
+        }
+    }
+
+    /// The settings file, one `key = value` line per setting.
+    pub fn to_toml(&self) -> String {
+        let clock = match self.time_control {
+            TimeControl::Unlimited => "none",
+            TimeControl::SuddenDeath(_) => "sudden-death",
+            TimeControl::Increment(..) => "increment",
+            TimeControl::ByoYomi(..) => "byo-yomi",
+        };
+        let timed = self.time_control != TimeControl::Unlimited;
+        let animation_speed = match self.animation_speed {
+            AnimationSpeed::Slow => "slow",
+            AnimationSpeed::Normal => "normal",
+            AnimationSpeed::Fast => "fast",
+        };
+        let number = |value: u64| toml::Value::Integer(value.try_into().unwrap_or(i64::MAX));
+        let mut file = toml::Table::new();
+        let mut put = |key: &str, value: toml::Value| {
+            file.insert(key.to_owned(), value);
+        };
+        put("color", color_name(self.chosen_color).into());
+        put("mode", mode_name(self.mode).into());
+        put("difficulty", difficulty_name(self.difficulty).into());
+        put("clock", clock.into());
+        if timed {
+            put(
+                "clock_minutes",
+                number(self.time_control.main().as_secs() / 60),
+            );
+            put("clock_seconds", number(self.time_control.extra().as_secs()));
+        }
+        put("display_size", self.display_size.name().into());
+        put("theme", self.theme.as_str().into());
+        put("show_coordinates", self.show_coordinates.into());
+        put("show_legal_moves", self.show_legal_moves.into());
+        put("show_flip_counts", self.show_flip_counts.into());
+        put("show_last_move", self.show_last_move.into());
+        put("show_evaluation", self.show_evaluation.into());
+        put("animations", self.animations.into());
+        put("animation_speed", animation_speed.into());
+        put("black_name", self.black_name.as_str().into());
+        put("white_name", self.white_name.as_str().into());
+        put("language", self.language.code().into());
+        put("accessibility", self.accessibility.into());
+        put("disc_symbols", self.disc_symbols.into());
+        put("ui_scale", i64::from(self.ui_scale).into());
+        file.to_string()
+    }
+
+    /// Reads what [`Settings::to_toml`] writes. Settings that are missing or have
+    /// a value this version doesn't know, or one of the wrong type, keep their
+    /// defaults, so an old or hand-edited file still loads. Clock times outside the
+    /// choices on the settings page are moved to the nearest end of them.
+    pub fn from_toml(contents: &str) -> Result<Settings, toml::de::Error> {
+        let file: toml::Table = contents.parse()?;
+        let text = |key| file.get(key).and_then(toml::Value::as_str);
+        let flag = |key| file.get(key).and_then(toml::Value::as_bool);
+        let number = |key| file.get(key).and_then(toml::Value::as_integer);
+        let defaults = Settings::default();
+        let within = |choices: &[u64], value: Option<i64>, default| {
+            let value = value.map_or(default, |value| u64::try_from(value).unwrap_or(0));
+            value.clamp(choices[0], choices[choices.len() - 1])
+        };
+        let main = Duration::from_secs(60 * within(&MINUTES, number("clock_minutes"), 5));
+        let extra = Duration::from_secs(within(&SECONDS, number("clock_seconds"), 10));
+        Ok(Settings {
+            chosen_color: text("color")
+                .and_then(color_from_name)
+                .unwrap_or(defaults.chosen_color),
+            mode: text("mode")
+                .and_then(mode_from_name)
+                .unwrap_or(defaults.mode),
+            difficulty: text("difficulty")
+                .and_then(difficulty_from_name)
+                .unwrap_or(defaults.difficulty),
+            time_control: match text("clock") {
+                Some("sudden-death") => TimeControl::SuddenDeath(main),
+                Some("increment") => TimeControl::Increment(main, extra),
+                Some("byo-yomi") => TimeControl::ByoYomi(main, extra),
+                _ => TimeControl::Unlimited,
+            },
+            display_size: text("display_size")
+                .and_then(DisplaySize::from_name)
+                .unwrap_or(defaults.display_size),
+            theme: text("theme").map_or(defaults.theme, str::to_owned),
+            show_coordinates: flag("show_coordinates").unwrap_or(defaults.show_coordinates),
+            show_legal_moves: flag("show_legal_moves").unwrap_or(defaults.show_legal_moves),
+            show_flip_counts: flag("show_flip_counts").unwrap_or(defaults.show_flip_counts),
+            show_last_move: flag("show_last_move").unwrap_or(defaults.show_last_move),
+            show_evaluation: flag("show_evaluation").unwrap_or(defaults.show_evaluation),
+            animations: flag("animations").unwrap_or(defaults.animations),
+            animation_speed: match text("animation_speed") {
+                Some("slow") => AnimationSpeed::Slow,
+                Some("fast") => AnimationSpeed::Fast,
+                _ => AnimationSpeed::Normal,
+            },
+            black_name: text("black_name").map_or(defaults.black_name, str::to_owned),
+            white_name: text("white_name").map_or(defaults.white_name, str::to_owned),
+            language: text("language")
+                .and_then(Language::from_code)
+                .unwrap_or(defaults.language),
+            accessibility: flag("accessibility").unwrap_or(defaults.accessibility),
+            disc_symbols: flag("disc_symbols").unwrap_or(defaults.disc_symbols),
+            ui_scale: number("ui_scale")
+                .and_then(|scale| u32::try_from(scale).ok())
+                .filter(|scale| UI_SCALES.contains(scale))
+                .unwrap_or(defaults.ui_scale),
+        })
+    }
+}
+
+/// `settings.toml` in the config directory, e.g. `~/.config/reversi_iced/settings.toml`
+/// on Linux.
+pub fn settings_path(paths: &Paths) -> Option<PathBuf> {
+    paths.config.as_ref().map(|dir| dir.join("settings.toml"))
+}
+
+/// The saved settings, or the defaults if there aren't any yet or the file isn't valid TOML.
+pub fn load(paths: &Paths) -> Settings {
+    let Some(contents) = settings_path(paths).and_then(|path| fs::read_to_string(path).ok()) else {
+        return Settings::default();
+    };
+    Settings::from_toml(&contents).unwrap_or_else(|error| {
+        eprintln!("Couldn't read the settings, using the defaults: {error}");
+        Settings::default()
+    })
+}
+
+pub fn store(paths: &Paths, settings: &Settings) -> io::Result<()> {
+    let path = settings_path(paths).ok_or(io::ErrorKind::NotFound)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, settings.to_toml())
+}
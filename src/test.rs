@@ -194,4 +194,1020 @@ mod tests {
         assert_eq!(board.win, control_board.win);
         assert_eq!(board.next_to_taken, control_board.next_to_taken);
     }
+
+    #[test]
+    fn saved_game_round_trip() {
+        let mut board = Board::new();
+        let mut history = Vec::new();
+        board.colored_move_recorded(
+            Message::EmptyPressed(2, 3),
+            Player,
+            Computer,
+            StoneColor::Black,
+            Difficulty::Easy,
+            &mut history,
+        );
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].square.map(square_name).unwrap(), "d3");
+        assert_eq!(history[1].color, StoneColor::White);
+
+        let saved = save::SavedGame {
+            opening: Board::new(),
+            board,
+            history,
+            chosen_color: StoneColor::Black,
+            mode: Mode::VsComputer,
+            difficulty: Difficulty::Easy,
+        };
+        let contents = saved.to_file_string();
+        assert_eq!(
+            save::SavedGame::from_file_string(&contents),
+            Some(saved.clone())
+        );
+
+        // Moves that don't lead to the saved position mean the file can't be trusted.
+        let edited = contents.replace(
+            &saved.board.to_position_string(),
+            &Board::new().to_position_string(),
+        );
+        assert_ne!(edited, contents);
+        assert_eq!(save::SavedGame::from_file_string(&edited), None);
+    }
+
+    #[test]
+    fn edited_moves_are_rejected() {
+        use crate::save::moves_from_string;
+        let opening = Board::new();
+        assert_eq!(moves_from_string(&opening, "D3 c3").unwrap().len(), 2);
+        // Black twice in a row, White first, and a pass with moves to make.
+        assert_eq!(moves_from_string(&opening, "D3 C5"), None);
+        assert_eq!(moves_from_string(&opening, "c3"), None);
+        assert_eq!(moves_from_string(&opening, "D3 pass"), None);
+        assert_eq!(moves_from_string(&opening, "PASS"), None);
+
+        assert_eq!(parse_square("D3"), Some(pair_to_index(2, 3)));
+        assert_eq!(parse_square("h8"), Some(pair_to_index(7, 7)));
+        for name in ["a+1", "a01", "a0", "a9", "i1", "a", "a 1", "a1 "] {
+            assert_eq!(parse_square(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn legal_moves_from_start() {
+        let board = Board::new();
+        let names: Vec<String> = board
+            .legal_moves(StoneColor::Black)
+            .into_iter()
+            .map(square_name)
+            .collect();
+        assert_eq!(names, ["d3", "c4", "f5", "e6"]);
+        assert_eq!(board.flips(pair_to_index(2, 3), StoneColor::Black), 1);
+        assert_eq!(board.flips(pair_to_index(0, 0), StoneColor::Black), 0);
+    }
+
+    #[test]
+    fn play_move_records_flipped_stones() {
+        let mut board = Board::new();
+        let ply = board
+            .play_move(pair_to_index(2, 3), StoneColor::Black)
+            .unwrap();
+        assert_eq!(ply.square, Some(pair_to_index(2, 3)));
+        assert_eq!(ply.flipped, [pair_to_index(3, 3)]);
+        assert_eq!(
+            board.play_move(pair_to_index(0, 0), StoneColor::White),
+            None
+        );
+    }
+
+    #[test]
+    fn board_from_history_matches_played_game() {
+        let mut board = Board::new();
+        let mut history = Vec::new();
+        for _ in 0..3 {
+            let (i, j) = index_to_pair(board.legal_moves(board.turn)[0]);
+            board.colored_move_recorded(
+                Message::EmptyPressed(i, j),
+                Player,
+                Player,
+                board.turn,
+                Difficulty::Easy,
+                &mut history,
+            );
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(Board::from_history(&history), board);
+        assert_eq!(Board::from_history(&[]), Board::new());
+    }
+
+    #[test]
+    fn pass_is_recorded_in_history() {
+        let mut board = pass_after_c1();
+        let mut history = Vec::new();
+        board.colored_move_recorded(
+            Message::EmptyPressed(0, 2),
+            Player,
+            Player,
+            StoneColor::Black,
+            Difficulty::Easy,
+            &mut history,
+        );
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].square, Some(pair_to_index(0, 2)));
+        assert_eq!(history[1], Ply::pass(StoneColor::White));
+        assert_eq!(board.turn, StoneColor::Black);
+        assert!(board.must_pass(StoneColor::White));
+        assert!(!board.must_pass(StoneColor::Black));
+    }
+
+    #[test]
+    fn evaluate_moves_covers_every_legal_move() {
+        let board = Board::new();
+        let evaluations = board.evaluate_moves(StoneColor::Black, Difficulty::Easy.depth());
+        let mut squares: Vec<usize> = evaluations.iter().map(|e| e.square).collect();
+        squares.sort();
+        assert_eq!(squares, board.legal_moves(StoneColor::Black));
+        assert!(evaluations.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(
+            board.best_move(StoneColor::Black, Difficulty::Easy.depth()),
+            Some(evaluations[0].square)
+        );
+        // Shallower than the shallowest search is the shallowest search.
+        for depth in [0, 1] {
+            assert_eq!(board.evaluate_moves(StoneColor::Black, depth), evaluations);
+        }
+    }
+
+    #[test]
+    fn review_annotates_every_move() {
+        let mut board = Board::new();
+        let mut history = Vec::new();
+        for _ in 0..4 {
+            let (i, j) = index_to_pair(board.legal_moves(board.turn)[0]);
+            board.colored_move_recorded(
+                Message::EmptyPressed(i, j),
+                Player,
+                Player,
+                board.turn,
+                Difficulty::Easy,
+                &mut history,
+            );
+        }
+        let annotations = review_game(&history, Difficulty::Easy.depth(), i32::MAX);
+        assert_eq!(annotations.len(), 4);
+        for (annotation, ply) in annotations.iter().zip(&history) {
+            assert_eq!(Some(annotation.played.square), ply.square);
+            assert!(annotation.loss >= 0);
+            assert!(!annotation.mistake);
+        }
+        assert!(review_game(&history, Difficulty::Easy.depth(), -1)
+            .iter()
+            .all(|annotation| annotation.mistake));
+    }
+
+    #[test]
+    fn clocks_increment_and_byo_yomi() {
+        use crate::clock::Clocks;
+        use std::time::{Duration, Instant};
+        let start = Instant::now();
+        let seconds = Duration::from_secs;
+
+        let increment = TimeControl::Increment(seconds(60), seconds(2));
+        let mut clocks = Clocks::new(increment, StoneColor::Black, start).unwrap();
+        clocks.spend(StoneColor::Black, seconds(10));
+        clocks.start(StoneColor::White, start + seconds(10));
+        assert_eq!(clocks.remaining(StoneColor::Black, start), seconds(52));
+        assert_eq!(
+            clocks.remaining(StoneColor::White, start + seconds(15)),
+            seconds(55)
+        );
+        assert_eq!(clocks.flagged(start + seconds(69)), None);
+        assert_eq!(clocks.flagged(start + seconds(70)), Some(StoneColor::White));
+
+        let byo_yomi = TimeControl::ByoYomi(seconds(5), seconds(10));
+        let mut clocks = Clocks::new(byo_yomi, StoneColor::Black, start).unwrap();
+        assert_eq!(
+            clocks.display(StoneColor::Black, start + seconds(8)),
+            "BY 0:07"
+        );
+        assert_eq!(clocks.flagged(start + seconds(14)), None);
+        clocks.spend(StoneColor::Black, seconds(14));
+        clocks.start(StoneColor::Black, start);
+        assert_eq!(clocks.flagged(start + seconds(9)), None);
+        assert_eq!(clocks.flagged(start + seconds(10)), Some(StoneColor::Black));
+
+        assert!(Clocks::new(TimeControl::Unlimited, StoneColor::Black, start).is_none());
+    }
+
+    #[test]
+    fn settings_toml_round_trip() {
+        use crate::settings::Settings;
+        use std::time::Duration;
+        let settings = Settings {
+            chosen_color: StoneColor::White,
+            mode: Mode::TwoPlayers,
+            difficulty: Difficulty::Easy,
+            time_control: TimeControl::ByoYomi(Duration::from_secs(180), Duration::from_secs(30)),
+            display_size: DisplaySize::Large,
+            theme: String::from("Wood"),
+            show_coordinates: false,
+            show_flip_counts: true,
+            animation_speed: AnimationSpeed::Fast,
+            black_name: String::from("Ann \"the\" \\ Black"),
+            accessibility: true,
+            ui_scale: 150,
+            ..Settings::default()
+        };
+        assert_eq!(Settings::from_toml(&settings.to_toml()), Ok(settings));
+        assert!(Settings::from_toml("mode = \"unterminated\n").is_err());
+        let wrong_type = Settings::from_toml("mode = 12\n# comment\ntheme = \"Wood\"\n").unwrap();
+        assert_eq!(wrong_type.mode, Settings::default().mode);
+        assert_eq!(wrong_type.theme, "Wood");
+        // Times are kept to the choices the settings page offers.
+        let clock = |file| Settings::from_toml(file).unwrap().time_control;
+        assert_eq!(
+            clock("clock = \"increment\"\nclock_minutes = 0\nclock_seconds = -4\n"),
+            TimeControl::Increment(Duration::from_secs(60), Duration::from_secs(2))
+        );
+        assert_eq!(
+            clock("clock = \"sudden-death\"\nclock_minutes = 9223372036854775807\n"),
+            TimeControl::SuddenDeath(Duration::from_secs(30 * 60))
+        );
+        let edited = Settings::from_toml(
+            "mode = 'two-players' # with a friend\nblack_name = \"Zo\\u00eb\"\ndifficulty = \"impossible\"\n\n[later]\nkey = 1\n",
+        )
+        .unwrap();
+        assert_eq!(edited.mode, Mode::TwoPlayers);
+        assert_eq!(edited.black_name, "Zoë");
+        assert_eq!(edited.difficulty, Difficulty::default());
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn user_themes_fill_in_from_built_ins() {
+        use crate::themes::parse_themes;
+        let themes = parse_themes(
+            "# my themes\n[Ocean]\nfelt = \"#1a4f7a\" # deep water\nwhite = \"nonsense\"\n\n[\"Night\"]\ndark = true\nhint = '#00ff00'\n\n[Broken]\ndark = \"yes\"\n",
+        )
+        .unwrap();
+        assert_eq!(themes.len(), 2);
+        assert_eq!(themes[0].name, "Ocean");
+        assert_eq!(themes[0].felt, iced::Color::from_rgb8(0x1a, 0x4f, 0x7a));
+        assert_eq!(themes[0].white, GameTheme::classic().white);
+        assert_eq!(themes[1].name, "Night");
+        assert_eq!(themes[1].felt, GameTheme::dark().felt);
+        assert_eq!(themes[1].hint, iced::Color::from_rgb8(0, 0xff, 0));
+        assert!(parse_themes("[Ocean\nfelt = \"#1a4f7a\"\n").is_err());
+    }
+
+    #[test]
+    fn status_line_announces_moves_and_passes() {
+        let english = Catalog::built_in(Language::English);
+        assert_eq!(
+            status_line(&[], &Board::new(), &english),
+            "New game. Black to move."
+        );
+        let mut board = pass_after_c1();
+        let mut history = Vec::new();
+        board.colored_move_recorded(
+            Message::EmptyPressed(0, 2),
+            Player,
+            Player,
+            StoneColor::Black,
+            Difficulty::Easy,
+            &mut history,
+        );
+        assert_eq!(
+            status_line(&history[..1], &Board::from_history(&history[..1]), &english),
+            "Black c1 flips b1. White to move."
+        );
+        assert_eq!(
+            status_line(&history, &board, &Catalog::built_in(Language::Polish)),
+            "Białe pasują. Ruch: Czarne."
+        );
+    }
+
+    #[test]
+    fn every_catalog_has_every_key() {
+        use crate::locale::parse_catalog;
+        use std::collections::BTreeSet;
+        let placeholders = |message: &str| -> BTreeSet<String> {
+            message
+                .split('{')
+                .skip(1)
+                .filter_map(|rest| rest.split_once('}'))
+                .map(|(name, _)| name.to_owned())
+                .collect()
+        };
+        let english = parse_catalog(Language::English.source()).unwrap();
+        assert!(english.contains_key("play"));
+        for language in Language::ALL {
+            let catalog = parse_catalog(language.source()).unwrap();
+            for (key, message) in &english {
+                let translated = catalog
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{} has no `{key}`", language.code()));
+                assert_eq!(
+                    placeholders(translated),
+                    placeholders(message),
+                    "{} `{key}`",
+                    language.code()
+                );
+            }
+            for key in catalog.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{} has unknown `{key}`",
+                    language.code()
+                );
+            }
+        }
+        let catalog = Catalog::built_in(Language::German);
+        assert_eq!(catalog.fill("wins", &[("name", &"Ann")]), "Ann gewinnt!");
+        assert_eq!(catalog.get("no_such_key"), "no_such_key");
+        // A name typed in is put in as it is, even if it looks like a placeholder.
+        let catalog = Catalog::built_in(Language::English);
+        assert_eq!(
+            catalog.fill("no_moves", &[("name", &"{other}"), ("other", &"Bo {")]),
+            "{other} has no moves — Bo { plays again"
+        );
+    }
+
+    #[test]
+    fn command_line_options() {
+        use crate::cli::Options;
+        let args = |args: &[&str]| Options::parse(args.iter().map(|arg| arg.to_string()));
+        let position = Board::new().to_position_string();
+        let options = args(&[
+            "--mode",
+            "two-players",
+            "--display-size=large",
+            "--position",
+            &position,
+            "--window-size",
+            "1280x800",
+            "--fullscreen",
+        ])
+        .unwrap();
+        assert_eq!(options.mode, Some(Mode::TwoPlayers));
+        assert_eq!(options.display_size, Some(DisplaySize::Large));
+        assert_eq!(options.position, Some(Board::new()));
+        assert_eq!(options.window_size, Some((1280, 800)));
+        assert!(options.fullscreen && options.starts_game());
+        assert!(!args(&["--display-size", "fit"]).unwrap().starts_game());
+        assert!(args(&["--color"]).is_err());
+        assert!(args(&["--difficulty", "impossible"]).is_err());
+        assert!(args(&["--window-size", "0x600"]).is_err());
+        assert!(args(&["--frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn saved_game_keeps_its_opening() {
+        let opening = pass_after_c1();
+        let mut board = opening.clone();
+        let ply = board
+            .play_move(pair_to_index(0, 2), StoneColor::Black)
+            .unwrap();
+        let history = vec![ply, Ply::pass(StoneColor::White)];
+        assert_eq!(opening.replay(&history).board, board.board);
+        let saved = save::SavedGame {
+            opening,
+            board: board.clone(),
+            history,
+            chosen_color: StoneColor::White,
+            mode: Mode::TwoPlayers,
+            difficulty: Difficulty::Hard,
+        };
+        assert_eq!(
+            save::SavedGame::from_file_string(&saved.to_file_string()),
+            Some(saved)
+        );
+    }
+
+    #[test]
+    fn terminal_keys_and_computer_reply() {
+        use crate::{
+            cli::Options,
+            settings::Settings,
+            tui::{decode_keys, Key, Tui},
+        };
+        assert_eq!(
+            decode_keys(b"\x1b[A\x1bOCq \r\x03\x1b"),
+            [
+                Key::Up,
+                Key::Right,
+                Key::Char('q'),
+                Key::Play,
+                Key::Play,
+                Key::Interrupt
+            ]
+        );
+        let options = Options {
+            mode: Some(Mode::VsComputer),
+            color: Some(StoneColor::White),
+            difficulty: Some(Difficulty::Easy),
+            ..Options::default()
+        };
+        let paths = Paths {
+            config: None,
+            data: None,
+        };
+        let mut tui = Tui::new(&Settings::default(), &paths, options);
+        assert!(tui.computer_to_move());
+        tui.computer_move();
+        assert!(!tui.computer_to_move());
+        assert!(tui.render().contains("1. Black"));
+        // The cursor starts on d4, which is taken.
+        tui.press(Key::Play);
+        assert!(!tui.computer_to_move());
+        tui.press(Key::Char('q'));
+        assert!(tui.quit);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn game_state_round_trips_through_json() {
+        let mut board = Board::new();
+        let ply = board
+            .play_move(pair_to_index(2, 3), StoneColor::Black)
+            .unwrap();
+        board.turn = StoneColor::White;
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, format!("\"{}\"", board.to_position_string()));
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+        assert!(serde_json::from_str::<Board>("\"XO\"").is_err());
+
+        let saved = save::SavedGame {
+            opening: Board::new(),
+            board,
+            history: vec![ply],
+            chosen_color: StoneColor::Black,
+            mode: Mode::VsComputer,
+            difficulty: Difficulty::Medium,
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+        assert_eq!(
+            serde_json::from_str::<save::SavedGame>(&json).unwrap(),
+            saved
+        );
+
+        let message = Message::MenuMessage(MenuItem::ChooseTimeControl(TimeControl::Increment(
+            std::time::Duration::from_secs(300),
+            std::time::Duration::from_secs(5),
+        )));
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+        assert!(serde_json::to_string(&Message::Tick(std::time::Instant::now())).is_err());
+
+        let settings: settings::Settings =
+            serde_json::from_str(r#"{"mode": "TwoPlayers", "ui_scale": 150}"#).unwrap();
+        assert_eq!(settings.mode, Mode::TwoPlayers);
+        assert_eq!(settings.theme, settings::Settings::default().theme);
+    }
+
+    #[test]
+    fn lan_packets_round_trip() {
+        use crate::net::{position_hash, with_default_port, Packet};
+        let mut board = Board::new();
+        let history = vec![board
+            .play_move(parse_square("d3").unwrap(), StoneColor::Black)
+            .unwrap()];
+        for packet in [
+            Packet::Hello {
+                version: 1,
+                room: None,
+                watch: false,
+            },
+            Packet::Hello {
+                version: 1,
+                room: Some(String::from("evening")),
+                watch: false,
+            },
+            Packet::Hello {
+                version: 1,
+                room: Some(String::from("evening")),
+                watch: true,
+            },
+            Packet::Game {
+                color: StoneColor::White,
+                opening: Board::new(),
+                history: history.clone(),
+            },
+            Packet::Game {
+                color: StoneColor::Black,
+                opening: board.clone(),
+                history: Vec::new(),
+            },
+            Packet::Move {
+                plies: 1,
+                square: parse_square("c3").unwrap(),
+                hash: position_hash(&board),
+            },
+            Packet::Resync,
+            Packet::Rematch,
+            Packet::Error(String::from("protocol version 2 isn't supported")),
+            Packet::Ping,
+        ] {
+            assert_eq!(Packet::from_line(&packet.to_line()), Some(packet));
+        }
+        assert_eq!(Packet::from_line("SHRUG 1 2 3"), None);
+        assert_eq!(Packet::from_line("MOVE 1 z9 00"), None);
+        assert_eq!(
+            position_hash(&board),
+            position_hash(&Board::from_position_string(&board.to_position_string()).unwrap())
+        );
+        assert_ne!(position_hash(&board), position_hash(&Board::new()));
+        assert_eq!(with_default_port("192.168.1.20"), "192.168.1.20:7878");
+        assert_eq!(with_default_port("localhost:9000"), "localhost:9000");
+    }
+
+    /// Black to move, and after Black's c1 White has nothing to play and passes.
+    fn pass_after_c1() -> Board {
+        Board::from_position_string(&format!(
+            "{}{}{}{} X",
+            "XO------",
+            "--------",
+            "XO------",
+            "-".repeat(5 * WIDTH)
+        ))
+        .unwrap()
+    }
+
+    /// One player's end of a networked game, kept the way the window keeps it.
+    struct Side {
+        session: crate::net::Session,
+        opening: Board,
+        board: Board,
+        history: Vec<Ply>,
+    }
+    impl Side {
+        fn new(session: crate::net::Session) -> Side {
+            Side {
+                session,
+                opening: Board::new(),
+                board: Board::new(),
+                history: Vec::new(),
+            }
+        }
+
+        /// Handles events until `done` says so.
+        fn wait_for(&mut self, done: impl Fn(&Side) -> bool) {
+            while !done(self) {
+                self.handle_next()
+            }
+        }
+
+        fn handle_next(&mut self) {
+            let event = self
+                .session
+                .events()
+                .next_within(std::time::Duration::from_secs(10))
+                .expect("the other side went quiet");
+            match self
+                .session
+                .handle(event, &self.opening, &self.history, &self.board)
+            {
+                Some(crate::net::Update::Play(square)) => self.play(square),
+                Some(crate::net::Update::Replace { opening, history }) => {
+                    self.board = opening.replay(&history);
+                    self.opening = opening;
+                    self.history = history;
+                }
+                Some(crate::net::Update::Rematch) | None => {}
+            }
+        }
+
+        fn play(&mut self, square: usize) {
+            let plies = self.history.len();
+            let (row, column) = index_to_pair(square);
+            self.board.colored_move_recorded(
+                Message::EmptyPressed(row, column),
+                Player,
+                Player,
+                self.board.turn,
+                Difficulty::Easy,
+                &mut self.history,
+            );
+            self.session.played(plies, &self.history, &self.board)
+        }
+    }
+
+    #[test]
+    fn lan_game_over_localhost() {
+        use crate::net::{Role, Session, Status};
+
+        let mut host = Side::new(Session::open(Role::Host(0), StoneColor::Black).unwrap());
+        let Role::Host(port) = host.session.role().clone() else {
+            unreachable!()
+        };
+        let join = || Session::open(Role::Join(format!("127.0.0.1:{port}")), StoneColor::Black);
+        let mut guest = Side::new(join().unwrap());
+        host.wait_for(|side| side.session.status == Status::Connected);
+        guest.wait_for(|side| side.session.status == Status::Connected);
+        assert_eq!(guest.session.color, StoneColor::White);
+
+        // Moves go both ways, each checked against the hash of where it led.
+        host.play(parse_square("d3").unwrap());
+        guest.wait_for(|side| side.history.len() == 1);
+        assert!(guest.session.can_move(&guest.board));
+        guest.play(parse_square("c3").unwrap());
+        host.wait_for(|side| side.history.len() == 2);
+        assert_eq!(host.board, guest.board);
+
+        // A guest that got out of step takes the host's game over.
+        guest.board.board[0] = Tile(Some(StoneColor::White));
+        host.play(parse_square("c4").unwrap());
+        guest.wait_for(|side| side.session.status == Status::Resyncing);
+        // The guest's request for the whole game.
+        host.handle_next();
+        guest.wait_for(|side| side.session.status == Status::Connected);
+        assert_eq!(guest.history, host.history);
+        assert_eq!(guest.board, host.board);
+
+        // A guest that comes back gets the game where it was left.
+        drop(guest);
+        host.wait_for(|side| matches!(side.session.status, Status::Lost(_)));
+        let mut guest = Side::new(join().unwrap());
+        host.wait_for(|side| side.session.status == Status::Connected);
+        guest.wait_for(|side| side.session.status == Status::Connected);
+        assert_eq!(guest.history, host.history);
+        assert!(guest.session.can_move(&guest.board));
+
+        // Nobody else can play, but anyone can watch: the game so far, then every
+        // move from either side.
+        let mut intruder = Side::new(join().unwrap());
+        intruder.wait_for(|side| matches!(side.session.status, Status::Refused(_)));
+        let watch = Role::Watch(format!("127.0.0.1:{port}"));
+        let mut spectator = Side::new(Session::open(watch, StoneColor::Black).unwrap());
+        // The spectator's arrival.
+        host.handle_next();
+        spectator.wait_for(|side| side.session.status == Status::Connected);
+        assert_eq!(spectator.history, host.history);
+        assert!(!spectator.session.can_move(&spectator.board));
+        guest.play(guest.board.legal_moves(guest.board.turn)[0]);
+        host.wait_for(|side| side.history.len() == 4);
+        host.play(host.board.legal_moves(host.board.turn)[0]);
+        spectator.wait_for(|side| side.history.len() == 5);
+        assert_eq!(spectator.board, host.board);
+        assert_eq!(spectator.session.status, Status::Connected);
+    }
+
+    #[test]
+    fn server_rooms_bots_and_archive() {
+        use crate::net::{position_hash, Role, Session, Status};
+        use crate::server::Server;
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpStream,
+            thread,
+            time::{Duration, Instant},
+        };
+        let games =
+            std::env::temp_dir().join(format!("reversi_server_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&games);
+        let server = Server::bind(0, games.clone()).unwrap();
+        let port = server.port().unwrap();
+        thread::spawn(move || server.run());
+        let join = |room: &str| {
+            Side::new(
+                Session::open(
+                    Role::Join(format!("127.0.0.1:{port}/{room}")),
+                    StoneColor::White,
+                )
+                .unwrap(),
+            )
+        };
+        let connected = |side: &Side| side.session.status == Status::Connected;
+
+        // Two players in a room, first come Black.
+        let mut black = join("evening");
+        black.wait_for(connected);
+        let mut white = join("evening");
+        white.wait_for(connected);
+        assert_eq!(black.session.color, StoneColor::Black);
+        assert_eq!(white.session.color, StoneColor::White);
+        black.play(parse_square("d3").unwrap());
+        white.wait_for(|side| side.history.len() == 1);
+        white.play(parse_square("c3").unwrap());
+        black.wait_for(|side| side.history.len() == 2);
+        assert_eq!(black.board, white.board);
+
+        // A spectator doesn't take a seat, and sees the game go on.
+        let watch = Role::Watch(format!("127.0.0.1:{port}/evening"));
+        let mut spectator = Side::new(Session::open(watch, StoneColor::White).unwrap());
+        spectator.wait_for(connected);
+        assert_eq!(spectator.history, black.history);
+        black.play(black.board.legal_moves(StoneColor::Black)[0]);
+        spectator.wait_for(|side| side.history.len() == 3);
+        assert_eq!(spectator.board, black.board);
+
+        // Asking for a rematch in the middle of a game does nothing.
+        white.wait_for(|side| side.history.len() == 3);
+        white.session.rematch();
+        white.play(white.board.legal_moves(StoneColor::White)[0]);
+        black.wait_for(|side| side.history.len() == 4);
+        assert_eq!(black.session.color, StoneColor::Black);
+        assert_eq!(black.board, white.board);
+
+        // A third player is turned away, and a raw client's illegal move is answered
+        // with the game as it really is.
+        let raw = |room: &str| {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            writeln!(stream, "HELLO {} {room}", net::PROTOCOL_VERSION).unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            (stream, reader.lines())
+        };
+        let (_, mut lines) = raw("evening");
+        assert_eq!(lines.next().unwrap().unwrap(), "ERROR room evening is full");
+        let (mut stream, mut lines) = raw("afternoon");
+        assert!(lines.next().unwrap().unwrap().starts_with("GAME black"));
+        writeln!(stream, "MOVE 0 a1 {:016x}", position_hash(&Board::new())).unwrap();
+        assert!(lines.next().unwrap().unwrap().starts_with("GAME black"));
+
+        // Against a bot, all the way to the end, which the server keeps.
+        let mut player = join("bot-easy-test");
+        player.wait_for(connected);
+        while player.board.win == GameOutcome::InProgress {
+            match player.session.can_move(&player.board) {
+                true => player.play(player.board.legal_moves(player.board.turn)[0]),
+                false => player.handle_next(),
+            }
+        }
+        let started = Instant::now();
+        let saved = loop {
+            let file = std::fs::read_dir(&games)
+                .ok()
+                .and_then(|mut files| files.next())
+                .and_then(|file| std::fs::read_to_string(file.ok()?.path()).ok());
+            match file {
+                Some(contents) => break save::SavedGame::from_file_string(&contents).unwrap(),
+                None if started.elapsed() < Duration::from_secs(10) => {
+                    thread::sleep(Duration::from_millis(50))
+                }
+                None => panic!("the finished game wasn't saved"),
+            }
+        };
+        assert_eq!(saved.history, player.history);
+        assert_eq!(saved.mode, Mode::VsComputer);
+        assert_eq!(saved.chosen_color, StoneColor::Black);
+
+        // A rematch swaps the colors, so the bot opens the next game.
+        player.session.rematch();
+        player.wait_for(|side| side.session.color == StoneColor::White && side.history.len() == 1);
+        assert_eq!(player.history[0].color, StoneColor::Black);
+        assert_eq!(player.board.win, GameOutcome::InProgress);
+        let _ = std::fs::remove_dir_all(&games);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn animation_turns_stones_nearest_first() {
+        use crate::gui::animation::Animation;
+        use iced::Color;
+        use std::time::{Duration, Instant};
+
+        // Black on a1 turns b1 over, then c1 one step later.
+        let mut board =
+            Board::from_position_string(&format!("-OOX----{} X", "-".repeat(7 * WIDTH))).unwrap();
+        let before = board.board.clone();
+        let ply = board.play_move(0, StoneColor::Black).unwrap();
+        assert_eq!(ply.flipped.len(), 2);
+        let speed = AnimationSpeed::Normal;
+        let (duration, step) = (speed.duration(), speed.step());
+        let start = Instant::now();
+        let animation = Animation::new(before, &[ply], speed, start);
+        let disc = |color| match color {
+            StoneColor::Black => Color::BLACK,
+            StoneColor::White => Color::WHITE,
+        };
+        let at = |square, time: Duration| animation.tile(square, start + time, disc);
+
+        // The new stone grows in from nothing, the others wait their turn.
+        assert_eq!(at(0, Duration::ZERO), Some((Some(Color::BLACK), 0.0)));
+        assert_eq!(at(1, Duration::ZERO), Some((Some(Color::WHITE), 1.0)));
+        assert_eq!(at(0, duration / 2), Some((Some(Color::BLACK), 0.5)));
+        // Once b1 has begun turning over, c1 is still waiting for its step.
+        let between = step + step / 2;
+        let (Some(turning), scale) = at(1, between).unwrap() else {
+            panic!("b1 has no stone")
+        };
+        assert_eq!(scale, 1.0);
+        assert!(turning.r > 0.0 && turning.r < 1.0);
+        assert_eq!(at(2, between), Some((Some(Color::WHITE), 1.0)));
+        let (Some(turning), _) = at(2, step * 2 + duration / 2).unwrap() else {
+            panic!("c1 has no stone")
+        };
+        assert!((turning.r - 0.5).abs() < 0.01);
+        // At the end every stone is black and full size, and untouched squares
+        // aren't animated at all.
+        let end = step * 2 + duration;
+        for square in 0..3 {
+            assert_eq!(at(square, end), Some((Some(Color::BLACK), 1.0)));
+        }
+        assert_eq!(at(3, Duration::ZERO), None);
+        assert!(!animation.is_finished(start + end - Duration::from_millis(1)));
+        assert!(animation.is_finished(start + end));
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn board_squares_under_the_pointer() {
+        use crate::gui::playboard::Grid;
+        use iced::{Point, Size};
+
+        // 900 by 900 gives 100 pixel squares and 50 pixels of felt all round.
+        let grid = Grid::new(Size::new(900.0, 900.0));
+        let at = |x, y| grid.square_at(Point::new(x, y));
+        assert_eq!(at(50.0, 50.0), Some(0));
+        assert_eq!(at(849.9, 50.0), Some(7));
+        assert_eq!(at(50.0, 849.9), Some(56));
+        assert_eq!(at(849.9, 849.9), Some(63));
+        assert_eq!(at(149.9, 150.0), Some(pair_to_index(1, 0)));
+        // The far edges and the felt belong to no square.
+        for (x, y) in [
+            (850.0, 400.0),
+            (400.0, 850.0),
+            (49.9, 400.0),
+            (400.0, 49.9),
+            (10.0, 10.0),
+            (899.0, 899.0),
+        ] {
+            assert_eq!(at(x, y), None, "({x}, {y})");
+        }
+
+        // A wide canvas keeps the board square and centered.
+        let grid = Grid::new(Size::new(900.0, 500.0));
+        for id in 0..WIDTH * HEIGHT {
+            assert_eq!(grid.square_at(grid.center(id)), Some(id));
+        }
+        let left = grid.center(0).x - 0.5 * (grid.center(1).x - grid.center(0).x);
+        assert_eq!(
+            grid.square_at(Point::new(left + 0.1, 250.0)),
+            Some(pair_to_index(4, 0))
+        );
+        assert_eq!(grid.square_at(Point::new(left - 0.1, 250.0)), None);
+        assert_eq!(grid.square_at(Point::new(100.0, 250.0)), None);
+
+        // No room for a board, no squares.
+        assert_eq!(Grid::new(Size::ZERO).square_at(Point::ORIGIN), None);
+    }
+
+    /// The window's game without the window, started with `options`. Its settings come
+    /// from, and its saves go to, a directory of its own, with a five minute clock for
+    /// both sides.
+    #[cfg(feature = "gui")]
+    fn window_game(options: cli::Options) -> Game {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static GAMES: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "reversi_window_test_{}_{}",
+            std::process::id(),
+            GAMES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let paths = Paths {
+            config: Some(dir.join("config")),
+            data: Some(dir.join("data")),
+        };
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(
+            dir.join("config").join("settings.toml"),
+            "clock = \"sudden-death\"\nclock_minutes = 5\n",
+        )
+        .unwrap();
+        Game::open(options, paths).0
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn undo_keeps_the_time_spent() {
+        use iced::Application;
+        use std::time::{Duration, Instant};
+        let mut game = window_game(cli::Options {
+            mode: Some(Mode::VsComputer),
+            color: Some(StoneColor::Black),
+            difficulty: Some(Difficulty::Easy),
+            ..Default::default()
+        });
+        let full = Duration::from_secs(5 * 60);
+        let (row, column) = index_to_pair(parse_square("d3").unwrap());
+        let _ = game.update(Message::EmptyPressed(row, column));
+        assert_eq!(game.history.len(), 2);
+        let clocks = game.clocks.as_ref().unwrap();
+        let white = clocks.remaining(StoneColor::White, Instant::now());
+        assert!(white < full);
+
+        // Neither side gets its time back, and the clock runs for the side to move.
+        for message in [Message::Undo, Message::Redo, Message::Undo] {
+            let _ = game.update(message);
+            let clocks = game.clocks.as_ref().unwrap();
+            let now = Instant::now();
+            assert_eq!(clocks.remaining(StoneColor::White, now), white);
+            assert!(clocks.remaining(StoneColor::Black, now) < full);
+        }
+        assert!(game.history.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn undo_and_redo_against_the_computer() {
+        use iced::Application;
+        let options = cli::Options {
+            mode: Some(Mode::VsComputer),
+            color: Some(StoneColor::Black),
+            difficulty: Some(Difficulty::Easy),
+            ..Default::default()
+        };
+        let press = |game: &mut Game, square| {
+            let (row, column) = index_to_pair(square);
+            let _ = game.update(Message::EmptyPressed(row, column));
+        };
+
+        // Each undo takes back a move and the computer's reply, and redo puts both back.
+        let mut game = window_game(options.clone());
+        press(&mut game, parse_square("d3").unwrap());
+        let after_one = (game.history.clone(), game.game_board.clone());
+        let legal = game.game_board.legal_moves(StoneColor::Black)[0];
+        press(&mut game, legal);
+        assert_eq!(game.history.len(), 4);
+        let after_two = (game.history.clone(), game.game_board.clone());
+        let _ = game.update(Message::Undo);
+        assert_eq!((game.history.clone(), game.game_board.clone()), after_one);
+        let _ = game.update(Message::Undo);
+        assert!(game.history.is_empty());
+        assert_eq!(game.game_board, Board::new());
+        // Nothing left to take back.
+        let _ = game.update(Message::Undo);
+        assert!(game.history.is_empty());
+        let _ = game.update(Message::Redo);
+        assert_eq!((game.history.clone(), game.game_board.clone()), after_one);
+        let _ = game.update(Message::Redo);
+        assert_eq!((game.history.clone(), game.game_board.clone()), after_two);
+
+        // White has no answer to c1 and passes; undo takes the pass back with the move.
+        let opening = pass_after_c1();
+        let mut game = window_game(cli::Options {
+            position: Some(opening.clone()),
+            ..options
+        });
+        press(&mut game, parse_square("c1").unwrap());
+        assert_eq!(game.history.len(), 2);
+        assert!(game.history[1].is_pass());
+        assert_eq!(game.game_board.turn, StoneColor::Black);
+        let passed = (game.history.clone(), game.game_board.clone());
+        let _ = game.update(Message::Undo);
+        assert!(game.history.is_empty());
+        assert_eq!(game.game_board, opening);
+        let _ = game.update(Message::Redo);
+        assert_eq!((game.history.clone(), game.game_board.clone()), passed);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn new_game_key_only_restarts_a_game_on_screen() {
+        use iced::Application;
+        // On the menu nothing starts, so neither do the clocks.
+        let mut game = window_game(cli::Options::default());
+        assert!(game.clocks.is_none());
+        let _ = game.update(Message::NewGame);
+        assert!(game.clocks.is_none());
+
+        let mut game = window_game(cli::Options {
+            mode: Some(Mode::TwoPlayers),
+            ..Default::default()
+        });
+        let (row, column) = index_to_pair(parse_square("d3").unwrap());
+        let _ = game.update(Message::EmptyPressed(row, column));
+        assert_eq!(game.history.len(), 1);
+        let _ = game.update(Message::NewGame);
+        assert!(game.history.is_empty());
+        assert!(game.clocks.is_some());
+    }
+
+    #[test]
+    fn catalogs_are_toml() {
+        use crate::locale::parse_catalog;
+        let catalog =
+            parse_catalog("# mine\nplay = \"Gra\\u0107\" # verb\nquit = 'C:\\bye'\n").unwrap();
+        assert_eq!(catalog["play"], "Grać");
+        assert_eq!(catalog["quit"], "C:\\bye");
+        assert!(parse_catalog("play = \"unterminated\n").is_err());
+        let catalog = parse_catalog("play = 1\nquit = \"Bye\"\n").unwrap();
+        assert!(!catalog.contains_key("play"));
+        assert_eq!(catalog["quit"], "Bye");
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn clicks_wait_for_the_players_turn() {
+        use iced::Application;
+        let (row, column) = index_to_pair(parse_square("d3").unwrap());
+        // Nobody has joined yet, so it isn't anyone's turn here.
+        let mut game = window_game(cli::Options {
+            color: Some(StoneColor::Black),
+            lan: Some(crate::net::Role::Host(0)),
+            ..Default::default()
+        });
+        let _ = game.update(Message::EmptyPressed(row, column));
+        assert!(game.history.is_empty());
+        assert_eq!(game.game_board, Board::new());
+    }
 }
@@ -0,0 +1,195 @@
+use crate::{Paths, StoneColor};
+use iced::{theme::Palette, Color, Theme};
+use std::{fs, path::PathBuf};
+
+/// Colors for the board, the discs and the rest of the window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameTheme {
+    pub name: String,
+    pub felt: Color,
+    pub lines: Color,
+    pub labels: Color,
+    pub black: Color,
+    pub white: Color,
+    pub last_move: Color,
+    pub hint: Color,
+    pub cursor: Color,
+    /// Menus and buttons; `success` is also the color picker's background.
+    pub palette: Palette,
+}
+
+impl GameTheme {
+    pub fn classic() -> Self {
+        GameTheme {
+            name: String::from("Classic"),
+            felt: Color::from_rgb(0.13, 0.45, 0.23),
+            lines: Color::from_rgb(0.04, 0.22, 0.1),
+            labels: Color::from_rgb(0.85, 0.92, 0.85),
+            black: Color::BLACK,
+            white: Color::WHITE,
+            last_move: Color::from_rgb(0.9, 0.2, 0.2),
+            hint: Color::from_rgb(1.0, 0.8, 0.2),
+            cursor: Color::from_rgb(0.4, 0.75, 1.0),
+            palette: Palette::LIGHT,
+        }
+    }
+
+    pub fn wood() -> Self {
+        let felt = Color::from_rgb(0.76, 0.56, 0.33);
+        GameTheme {
+            name: String::from("Wood"),
+            felt,
+            lines: Color::from_rgb(0.42, 0.27, 0.13),
+            labels: Color::from_rgb(0.3, 0.18, 0.08),
+            black: Color::from_rgb(0.12, 0.1, 0.08),
+            white: Color::from_rgb(0.98, 0.95, 0.88),
+            last_move: Color::from_rgb(0.75, 0.1, 0.1),
+            hint: Color::from_rgb(0.2, 0.45, 0.9),
+            cursor: Color::from_rgb(0.1, 0.3, 0.8),
+            palette: Palette {
+                background: Color::from_rgb(0.96, 0.92, 0.85),
+                text: Color::from_rgb(0.2, 0.13, 0.06),
+                primary: Color::from_rgb(0.55, 0.33, 0.15),
+                success: felt,
+                danger: Palette::LIGHT.danger,
+            },
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        GameTheme {
+            name: String::from("High contrast"),
+            felt: Color::from_rgb(0.0, 0.5, 0.0),
+            lines: Color::BLACK,
+            labels: Color::WHITE,
+            black: Color::BLACK,
+            white: Color::WHITE,
+            last_move: Color::from_rgb(1.0, 0.0, 1.0),
+            hint: Color::from_rgb(1.0, 1.0, 0.0),
+            cursor: Color::from_rgb(0.0, 1.0, 1.0),
+            palette: Palette {
+                background: Color::BLACK,
+                text: Color::WHITE,
+                primary: Color::from_rgb(1.0, 1.0, 0.0),
+                success: Color::from_rgb(0.0, 0.5, 0.0),
+                danger: Color::from_rgb(1.0, 0.0, 0.0),
+            },
+        }
+    }
+
+    pub fn dark() -> Self {
+        let felt = Color::from_rgb(0.16, 0.2, 0.18);
+        GameTheme {
+            name: String::from("Dark"),
+            felt,
+            lines: Color::from_rgb(0.06, 0.08, 0.07),
+            labels: Color::from_rgb(0.6, 0.65, 0.6),
+            black: Color::from_rgb(0.05, 0.05, 0.05),
+            white: Color::from_rgb(0.82, 0.82, 0.82),
+            last_move: Color::from_rgb(0.85, 0.3, 0.3),
+            hint: Color::from_rgb(0.9, 0.7, 0.2),
+            cursor: Color::from_rgb(0.35, 0.6, 0.9),
+            palette: Palette {
+                success: felt,
+                ..Palette::DARK
+            },
+        }
+    }
+
+    pub fn built_in() -> Vec<GameTheme> {
+        vec![
+            Self::classic(),
+            Self::wood(),
+            Self::high_contrast(),
+            Self::dark(),
+        ]
+    }
+
+    pub fn disc(&self, color: StoneColor) -> Color {
+        match color {
+            StoneColor::Black => self.black,
+            StoneColor::White => self.white,
+        }
+    }
+
+    /// The iced theme for everything that isn't the board.
+    pub fn iced_theme(&self) -> Theme {
+        Theme::custom(self.name.clone(), self.palette)
+    }
+}
+
+/// Reads themes from a TOML file with one table per theme, named after it:
+///
+/// ```toml
+/// [Ocean]
+/// felt = "#1a4f7a"
+/// lines = "#0b2a44"
+/// ```
+///
+/// Colors are `"#rrggbb"`. Anything left out comes from "Classic", or from "Dark"
+/// with `dark = true`. Colors that can't be read are left out, and so is anything
+/// that isn't a theme's table.
+pub fn parse_themes(contents: &str) -> Result<Vec<GameTheme>, toml::de::Error> {
+    let table: toml::Table = contents.parse()?;
+    Ok(table
+        .into_iter()
+        .filter_map(|(name, theme)| read_theme(name, theme.as_table()?))
+        .collect())
+}
+
+/// One table of `themes.toml`, or `None` if one of its keys has the wrong type.
+fn read_theme(name: String, table: &toml::Table) -> Option<GameTheme> {
+    let base = match table.get("dark").map(toml::Value::as_bool) {
+        Some(None) => return None,
+        Some(Some(true)) => GameTheme::dark(),
+        _ => GameTheme::classic(),
+    };
+    let color = |key: &str, default: Color| match table.get(key) {
+        Some(value) => Some(parse_color(value.as_str()?).unwrap_or(default)),
+        None => Some(default),
+    };
+    Some(GameTheme {
+        name,
+        felt: color("felt", base.felt)?,
+        lines: color("lines", base.lines)?,
+        labels: color("labels", base.labels)?,
+        black: color("black", base.black)?,
+        white: color("white", base.white)?,
+        last_move: color("last_move", base.last_move)?,
+        hint: color("hint", base.hint)?,
+        cursor: color("cursor", base.cursor)?,
+        palette: Palette {
+            background: color("background", base.palette.background)?,
+            text: color("text", base.palette.text)?,
+            primary: color("primary", base.palette.primary)?,
+            ..base.palette
+        },
+    })
+}
+
+/// `#rrggbb`
+fn parse_color(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |at: usize| u8::from_str_radix(hex.get(at..at + 2)?, 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// `themes.toml` in the config directory, next to the settings.
+pub fn themes_path(paths: &Paths) -> Option<PathBuf> {
+    paths.config.as_ref().map(|dir| dir.join("themes.toml"))
+}
+
+/// The built-in themes followed by the user's own, if they have any.
+pub fn load(paths: &Paths) -> Vec<GameTheme> {
+    let mut themes = GameTheme::built_in();
+    if let Some(contents) = themes_path(paths).and_then(|path| fs::read_to_string(path).ok()) {
+        match parse_themes(&contents) {
+            Ok(user_themes) => themes.extend(user_themes),
+            Err(error) => eprintln!("Couldn't read your themes: {error}"),
+        }
+    }
+    themes
+}
@@ -0,0 +1,386 @@
+use crate::{
+    cli::Options, index_to_pair, pair_to_index, settings, square_name, status_line, Board, Catalog,
+    Difficulty, Evaluation, GameOutcome, Message, Mode, Paths, PlayerOrComputer::Player, Ply,
+    StoneColor, HEIGHT, WIDTH,
+};
+use std::{
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+};
+
+/// A key the terminal front-end understands.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Enter or Space.
+    Play,
+    Char(char),
+    /// Ctrl-C, which raw mode delivers as a byte instead of a signal.
+    Interrupt,
+}
+
+/// Splits what the terminal sent into keys. Arrows come as `ESC [ A` or `ESC O A`;
+/// anything else that isn't printable is dropped.
+pub fn decode_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut bytes = bytes.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        let key = match byte {
+            0x1b if matches!(bytes.peek(), Some(b'[' | b'O')) => {
+                bytes.next();
+                match bytes.next() {
+                    Some(b'A') => Key::Up,
+                    Some(b'B') => Key::Down,
+                    Some(b'C') => Key::Right,
+                    Some(b'D') => Key::Left,
+                    _ => continue,
+                }
+            }
+            b'\r' | b'\n' | b' ' => Key::Play,
+            0x03 => Key::Interrupt,
+            byte if byte.is_ascii_graphic() => Key::Char(byte as char),
+            _ => continue,
+        };
+        keys.push(key)
+    }
+    keys
+}
+
+/// Playing in a terminal, with the same rules, modes and engine as the window.
+pub struct Tui {
+    board: Board,
+    opening: Board,
+    history: Vec<Ply>,
+    redo: Vec<Vec<Ply>>,
+    mode: Mode,
+    /// The color the local player has against the computer.
+    color: StoneColor,
+    difficulty: Difficulty,
+    cursor: (usize, usize),
+    /// The engine's take on the position: a hint, or every move in analysis mode.
+    evaluations: Vec<Evaluation>,
+    catalog: Catalog,
+    pub quit: bool,
+}
+
+/// How many of the engine's suggestions a hint shows.
+const HINT_MOVES: usize = 3;
+/// How many of the latest moves are listed next to the board.
+const LISTED_MOVES: usize = HEIGHT + 1;
+
+impl Tui {
+    /// A game set up from the saved settings, with `options` taking precedence.
+    /// Translation fixes come from `paths`.
+    pub fn new(settings: &settings::Settings, paths: &Paths, options: Options) -> Tui {
+        let mut tui = Tui {
+            board: Board::new(),
+            opening: Board::new(),
+            history: Vec::new(),
+            redo: Vec::new(),
+            mode: settings.mode,
+            color: settings.chosen_color,
+            difficulty: settings.difficulty,
+            cursor: (3, 3),
+            evaluations: Vec::new(),
+            catalog: Catalog::new(settings.language, paths),
+            quit: false,
+        };
+        if let Some(saved) = options.game {
+            tui.opening = saved.opening;
+            tui.board = saved.board;
+            tui.history = saved.history;
+            tui.mode = saved.mode;
+            tui.color = saved.chosen_color;
+            tui.difficulty = saved.difficulty;
+        }
+        if let Some(board) = options.position {
+            tui.opening = board.clone();
+            tui.board = board;
+        }
+        tui.mode = options.mode.unwrap_or(tui.mode);
+        tui.color = options.color.unwrap_or(tui.color);
+        tui.difficulty = options.difficulty.unwrap_or(tui.difficulty);
+        tui.analyse();
+        tui
+    }
+
+    /// Whether the engine should move next.
+    pub fn computer_to_move(&self) -> bool {
+        self.mode == Mode::VsComputer
+            && self.board.win == GameOutcome::InProgress
+            && self.board.turn != self.color
+    }
+
+    pub fn computer_move(&mut self) {
+        let color = self.board.turn;
+        match self.board.best_move(color, self.difficulty.depth()) {
+            Some(square) => self.play(square),
+            // Only a hand-made starting position can leave the side to move stuck.
+            None => {
+                self.history.push(Ply::pass(color));
+                self.board.turn = color.reverse();
+                self.analyse()
+            }
+        }
+    }
+
+    /// Plays `square` for the side to move, passing for the other side if it has to.
+    fn play(&mut self, square: usize) {
+        let plies = self.history.len();
+        let (row, column) = index_to_pair(square);
+        self.board.colored_move_recorded(
+            Message::EmptyPressed(row, column),
+            Player,
+            Player,
+            self.board.turn,
+            self.difficulty,
+            &mut self.history,
+        );
+        if self.history.len() != plies {
+            self.redo.clear();
+            self.analyse()
+        }
+    }
+
+    /// Whether a key press may put a disc down: never for the computer's side.
+    fn players_turn(&self) -> bool {
+        self.board.win == GameOutcome::InProgress && !self.computer_to_move()
+    }
+
+    /// Refreshes the move scores analysis mode shows, and drops any old hint.
+    fn analyse(&mut self) {
+        self.evaluations = match self.mode == Mode::Analysis && self.players_turn() {
+            true => self
+                .board
+                .evaluate_moves(self.board.turn, self.difficulty.depth()),
+            false => Vec::new(),
+        };
+    }
+
+    fn replace_history(&mut self, history: Vec<Ply>) {
+        self.board = self.opening.replay(&history);
+        self.history = history;
+        self.analyse()
+    }
+
+    pub fn press(&mut self, key: Key) {
+        let (row, column) = self.cursor;
+        match key {
+            Key::Up => self.cursor.0 = (row + HEIGHT - 1) % HEIGHT,
+            Key::Down => self.cursor.0 = (row + 1) % HEIGHT,
+            Key::Left => self.cursor.1 = (column + WIDTH - 1) % WIDTH,
+            Key::Right => self.cursor.1 = (column + 1) % WIDTH,
+            Key::Play if self.players_turn() => self.play(pair_to_index(row, column)),
+            Key::Play => {}
+            Key::Char('h') if self.players_turn() && self.mode != Mode::Analysis => {
+                self.evaluations = self
+                    .board
+                    .evaluate_moves(self.board.turn, self.difficulty.depth());
+                self.evaluations.truncate(HINT_MOVES)
+            }
+            Key::Char('u') => {
+                // Against the computer, take back to the local player's last move.
+                let computer = (self.mode == Mode::VsComputer).then(|| self.color.reverse());
+                if let Some(taken) = self
+                    .history
+                    .iter()
+                    .rposition(|ply| !ply.is_pass() && Some(ply.color) != computer)
+                {
+                    let mut history = self.history.clone();
+                    self.redo.push(history.split_off(taken));
+                    self.replace_history(history)
+                }
+            }
+            Key::Char('r') => {
+                if let Some(plies) = self.redo.pop() {
+                    let mut history = self.history.clone();
+                    history.extend(plies);
+                    self.replace_history(history)
+                }
+            }
+            Key::Char('n') => {
+                self.opening = Board::new();
+                self.redo.clear();
+                self.replace_history(Vec::new())
+            }
+            Key::Char('q') | Key::Interrupt => self.quit = true,
+            Key::Char(_) => {}
+        }
+    }
+
+    /// The whole screen, redrawn from the top-left corner.
+    pub fn render(&self) -> String {
+        let catalog = &self.catalog;
+        let mode = match self.mode {
+            Mode::VsComputer => format!(
+                "{} ({})",
+                catalog.get("vs_computer"),
+                catalog.get(match self.difficulty {
+                    Difficulty::Easy => "easy",
+                    Difficulty::Medium => "medium",
+                    Difficulty::Hard => "hard",
+                })
+            ),
+            Mode::TwoPlayers => catalog.get("two_players").to_owned(),
+            Mode::Analysis => catalog.get("analysis").to_owned(),
+        };
+        let mut screen = format!(
+            "\x1b[H\x1b[2J{}  —  {mode}\n{} {} : {} {}\n\n",
+            catalog.get("title"),
+            catalog.color(StoneColor::Black),
+            self.board.black_count,
+            self.board.white_count,
+            catalog.color(StoneColor::White),
+        );
+
+        let legal = match self.players_turn() {
+            true => self.board.legal_moves(self.board.turn),
+            false => Vec::new(),
+        };
+        let last_move = self.history.iter().rev().find_map(|ply| ply.square);
+        let mut lines = vec![format!(
+            "   {}",
+            (0..WIDTH)
+                .map(|column| format!(" {} ", (b'a' + column as u8) as char))
+                .collect::<String>()
+        )];
+        for row in 0..HEIGHT {
+            let mut line = format!("{:>2} \x1b[42m", row + 1);
+            for column in 0..WIDTH {
+                let id = pair_to_index(row, column);
+                let disc = match self.board.board[id].0 {
+                    Some(StoneColor::Black) => "\x1b[30m●",
+                    Some(StoneColor::White) => "\x1b[97m●",
+                    None if legal.contains(&id) => "\x1b[33m·",
+                    None => " ",
+                };
+                // Brackets show the cursor, parentheses the last move; neither needs color.
+                let (open, close) = match ((row, column) == self.cursor, last_move == Some(id)) {
+                    (true, _) => ("\x1b[93m[", "\x1b[93m]"),
+                    (false, true) => ("\x1b[31m(", "\x1b[31m)"),
+                    (false, false) => (" ", " "),
+                };
+                line += &format!("{open}{disc}{close}");
+            }
+            lines.push(line + "\x1b[0m");
+        }
+
+        let mut entries: Vec<String> = Vec::new();
+        let mut number = 0;
+        for ply in &self.history {
+            let color = catalog.color(ply.color);
+            entries.push(match ply.square {
+                Some(square) => {
+                    number += 1;
+                    catalog.fill(
+                        "move_entry",
+                        &[
+                            ("number", &number),
+                            ("color", &color),
+                            ("square", &square_name(square)),
+                        ],
+                    )
+                }
+                None => catalog.fill("passes", &[("color", &color)]),
+            })
+        }
+        let listed = &entries[entries.len().saturating_sub(LISTED_MOVES)..];
+        for (n, line) in lines.iter().enumerate() {
+            screen += line;
+            if let Some(entry) = listed.get(n) {
+                screen += &format!("    {entry}")
+            }
+            screen += "\n";
+        }
+
+        screen += "\n";
+        screen += &status_line(&self.history, &self.board, catalog);
+        screen += "\n";
+        screen += &self
+            .evaluations
+            .iter()
+            .map(|evaluation| {
+                format!(
+                    "{} ({:+})",
+                    square_name(evaluation.square),
+                    evaluation.differential
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("   ");
+        screen += "\n\n";
+        screen += catalog.get("tui_help");
+        screen
+    }
+}
+
+/// Puts the terminal into raw mode and the alternate screen until dropped.
+struct RawTerminal {
+    saved: String,
+}
+impl RawTerminal {
+    fn enter() -> io::Result<RawTerminal> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(RawTerminal {
+            saved: saved.trim().to_owned(),
+        })
+    }
+}
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Runs `stty` on the terminal the game reads from.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        )),
+    }
+}
+
+/// Plays until the player quits.
+pub fn run(options: Options) -> io::Result<()> {
+    let paths = Paths::user();
+    let mut tui = Tui::new(&settings::load(&paths), &paths, options);
+    let _terminal = RawTerminal::enter()?;
+    let (mut stdin, mut stdout) = (io::stdin(), io::stdout());
+    let mut input = [0; 32];
+    while !tui.quit {
+        if tui.computer_to_move() {
+            write!(
+                stdout,
+                "{}\n{}",
+                tui.render(),
+                tui.catalog.get("tui_thinking")
+            )?;
+            stdout.flush()?;
+            tui.computer_move();
+            continue;
+        }
+        write!(stdout, "{}", tui.render())?;
+        stdout.flush()?;
+        let read = stdin.read(&mut input)?;
+        if read == 0 {
+            break;
+        }
+        for key in decode_keys(&input[..read]) {
+            tui.press(key)
+        }
+    }
+    Ok(())
+}